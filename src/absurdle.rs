@@ -0,0 +1,131 @@
+use super::game::*;
+use super::solver::partition;
+use super::wordlist::WordList;
+use std::sync::Arc;
+
+/// Adversarial game with no fixed answer. Each guess keeps the largest group
+/// of remaining answers sharing one feedback pattern and reports that
+/// pattern, so the game only ends once a single word is left and guessed.
+#[derive(Debug, Clone)]
+pub struct Absurdle {
+    remaining: Vec<String>,
+    remaining_encoded: Vec<Vec<u8>>,
+    candidates: WordList,
+    round: usize,
+    pub state: GameState,
+    history: Vec<(String, Arc<Match>)>,
+}
+
+impl Absurdle {
+    /// Play over the answer and guess lists of `game`.
+    pub fn new(game: &Game) -> Absurdle {
+        Self::from_word_lists(&game.answers, &game.candidates)
+    }
+    pub fn from_word_lists(answers: &WordList, candidates: &WordList) -> Absurdle {
+        Absurdle {
+            remaining: answers.words().to_vec(),
+            remaining_encoded: answers.encoded().to_vec(),
+            candidates: candidates.clone(),
+            round: 0,
+            state: GameState::On,
+            history: Vec::new(),
+        }
+    }
+    /// Answers still consistent with every pattern returned so far.
+    pub fn remaining(&self) -> &[String] {
+        &self.remaining
+    }
+    pub fn round(&self) -> usize {
+        self.round
+    }
+    pub fn history(&self) -> &[(String, Arc<Match>)] {
+        &self.history
+    }
+    pub fn validate_guess(&self, guess: &Guess) -> Result<(), GuessError> {
+        if self.state != GameState::On {
+            return Err(GuessError::GameFinished);
+        }
+        if !self.candidates.contains(&guess.state) {
+            return Err(GuessError::NotInWordList);
+        }
+        Ok(())
+    }
+    /// Keep the largest pattern group for `guess` and return its pattern.
+    /// Ties go to the pattern revealing the least, i.e. the lowest index.
+    pub fn submit(&mut self, guess: &Guess) -> Result<Arc<Match>, GuessError> {
+        self.validate_guess(guess)?;
+        let word = &guess.state;
+        let symbols = self
+            .candidates
+            .alphabet()
+            .encode(word)
+            .expect("valid guess is spelled in the alphabet");
+        let pattern_matched = partition(&symbols, self.remaining_encoded.iter());
+        let mut best = 0;
+        for (index, count) in pattern_matched.iter().enumerate() {
+            if *count > pattern_matched[best] {
+                best = index;
+            }
+        }
+        (self.remaining, self.remaining_encoded) = std::mem::take(&mut self.remaining)
+            .into_iter()
+            .zip(std::mem::take(&mut self.remaining_encoded))
+            .filter(|(_, answer)| grade_symbols(&symbols, answer).pattern_index() == best)
+            .unzip();
+
+        let one_match = Arc::new(Match::from_pattern_index(
+            best,
+            self.candidates.word_length(),
+        ));
+        self.history.push((word.clone(), one_match.clone()));
+        if one_match.is_correct() {
+            self.state = GameState::Correct;
+        } else {
+            self.round += 1;
+        }
+        Ok(one_match)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guess(word: &str) -> Guess {
+        Guess {
+            state: word.to_string(),
+        }
+    }
+
+    fn absurdle(answers: &[&str]) -> Absurdle {
+        let answers = WordList::from_words(answers, 5).unwrap();
+        let candidates = WordList::from_words(TEST_WORDS, 5).unwrap();
+        Absurdle::from_word_lists(&answers, &candidates)
+    }
+
+    #[test]
+    fn guesses_keep_the_largest_group() {
+        let mut game = absurdle(&TEST_WORDS);
+        let one_match = game.submit(&guess("sissy")).unwrap();
+        assert_eq!(one_match.pattern_index(), 0);
+        assert_eq!(game.remaining(), ["rebut", "humph"]);
+        assert_eq!(game.round(), 1);
+        assert_eq!(game.state, GameState::On);
+    }
+
+    #[test]
+    fn ties_keep_the_lowest_pattern() {
+        let mut game = absurdle(&["sissy", "humph"]);
+        let one_match = game.submit(&guess("cigar")).unwrap();
+        assert_eq!(one_match.pattern_index(), 0);
+        assert_eq!(game.remaining(), ["humph"]);
+
+        assert!(game.submit(&guess("humph")).unwrap().is_correct());
+        assert_eq!(game.state, GameState::Correct);
+        assert_eq!(game.history().len(), 2);
+        assert_eq!(
+            game.submit(&guess("humph")).unwrap_err(),
+            GuessError::GameFinished
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use super::error::{Error, Result};
+
+/// The set of symbols words are spelled with. Words are stored as indices
+/// into this set, so any alphabet of up to 256 symbols can be played.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alphabet {
+    name: String,
+    symbols: Vec<char>,
+}
+
+impl Alphabet {
+    /// Build an alphabet from its symbols in keyboard order. Symbols are
+    /// lowercased; duplicates are dropped. It must end up with 1 to 256
+    /// symbols.
+    pub fn new<I: IntoIterator<Item = char>>(name: &str, symbols: I) -> Result<Alphabet> {
+        let alphabet = Self::collect(name, symbols);
+        if alphabet.is_empty() || alphabet.len() > 256 {
+            return Err(Error::InvalidAlphabetSize(alphabet.len()));
+        }
+        Ok(alphabet)
+    }
+    fn collect<I: IntoIterator<Item = char>>(name: &str, symbols: I) -> Alphabet {
+        let mut list: Vec<char> = Vec::new();
+        for symbol in symbols.into_iter().map(lowercase) {
+            if !list.contains(&symbol) {
+                list.push(symbol);
+            }
+        }
+        Alphabet {
+            name: name.to_string(),
+            symbols: list,
+        }
+    }
+    /// Lowercase `a`-`z`.
+    pub fn english() -> Alphabet {
+        Self::collect("english", 'a'..='z')
+    }
+    /// `a`-`z` with `ñ`.
+    pub fn spanish() -> Alphabet {
+        Self::collect("spanish", ('a'..='n').chain(['ñ']).chain('o'..='z'))
+    }
+    /// `a`-`z` with the umlauts and `ß`.
+    pub fn german() -> Alphabet {
+        Self::collect("german", ('a'..='z').chain(['ä', 'ö', 'ü', 'ß']))
+    }
+    /// Russian Cyrillic, `а`-`я` with `ё`.
+    pub fn russian() -> Alphabet {
+        Self::collect("russian", ('а'..='е').chain(['ё']).chain('ж'..='я'))
+    }
+    /// Digits and arithmetic symbols, for equation puzzles.
+    pub fn nerdle() -> Alphabet {
+        Self::collect("nerdle", "0123456789+-*/=".chars())
+    }
+    /// A built-in alphabet by name, or an ad-hoc one when `name` spells out
+    /// its symbols after a `symbols:` prefix, e.g.
+    /// `symbols:abcdefghijklmnñopqrstuvwxyz`.
+    pub fn by_name(name: &str) -> Result<Alphabet> {
+        match name.trim() {
+            "english" => Ok(Self::english()),
+            "spanish" => Ok(Self::spanish()),
+            "german" => Ok(Self::german()),
+            "russian" => Ok(Self::russian()),
+            "nerdle" => Ok(Self::nerdle()),
+            name => match name.strip_prefix("symbols:") {
+                Some(symbols) => Self::new("custom", symbols.trim().chars()),
+                None => Err(Error::UnknownAlphabet(name.to_string())),
+            },
+        }
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn symbols(&self) -> &[char] {
+        &self.symbols
+    }
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+    /// Map typed input onto the alphabet: lowercase it and keep it only if
+    /// it is one of the symbols.
+    pub fn normalize_char(&self, c: char) -> Option<char> {
+        let c = lowercase(c);
+        self.symbols.contains(&c).then_some(c)
+    }
+    pub fn index_of(&self, c: char) -> Option<u8> {
+        let c = lowercase(c);
+        self.symbols.iter().position(|s| *s == c).map(|i| i as u8)
+    }
+    pub fn symbol(&self, index: u8) -> char {
+        self.symbols[index as usize]
+    }
+    /// Word as symbol indices, `None` if it uses a symbol outside the alphabet.
+    pub fn encode(&self, word: &str) -> Option<Vec<u8>> {
+        word.chars().map(|c| self.index_of(c)).collect()
+    }
+    pub fn decode(&self, symbols: &[u8]) -> String {
+        symbols.iter().map(|index| self.symbol(*index)).collect()
+    }
+    /// Check that `word` has `word_length` symbols, all from this alphabet.
+    pub fn check_word(&self, word: &str, word_length: usize) -> Result<()> {
+        if let Some(character) = word.chars().find(|c| !self.symbols.contains(c)) {
+            return Err(Error::InvalidCharacter {
+                word: word.to_string(),
+                character,
+            });
+        }
+        if word.chars().count() != word_length {
+            return Err(Error::WrongLength {
+                word: word.to_string(),
+                expected: word_length,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+/// Single-symbol lowercase; characters whose lowercase form is longer than
+/// one symbol are kept as they are.
+fn lowercase(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(l), None) => l,
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_pick_built_in_alphabets() {
+        assert_eq!(Alphabet::by_name(" spanish ").unwrap(), Alphabet::spanish());
+        assert!(matches!(
+            Alphabet::by_name("klingon"),
+            Err(Error::UnknownAlphabet(name)) if name == "klingon"
+        ));
+        assert!(matches!(
+            Alphabet::by_name("abcde"),
+            Err(Error::UnknownAlphabet(_))
+        ));
+    }
+
+    #[test]
+    fn ad_hoc_alphabets_need_the_symbols_prefix() {
+        let custom = Alphabet::by_name("symbols: abcABC").unwrap();
+        assert_eq!(custom.name(), "custom");
+        assert_eq!(custom.symbols(), ['a', 'b', 'c']);
+        assert!(matches!(
+            Alphabet::by_name("symbols:"),
+            Err(Error::InvalidAlphabetSize(0))
+        ));
+    }
+
+    #[test]
+    fn alphabets_hold_at_most_256_symbols() {
+        let symbols = (0x4e00..0x4e00 + 257).filter_map(char::from_u32);
+        assert!(matches!(
+            Alphabet::new("hanzi", symbols),
+            Err(Error::InvalidAlphabetSize(257))
+        ));
+        let symbols = (0x4e00..0x4e00 + 256).filter_map(char::from_u32);
+        assert_eq!(Alphabet::new("hanzi", symbols).unwrap().len(), 256);
+    }
+}
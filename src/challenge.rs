@@ -0,0 +1,216 @@
+use super::error::{Error, Result};
+use super::game::*;
+
+const CHALLENGE_VERSION: u8 = 1;
+/// Crockford base32, which leaves out the easily confused I, L, O and U.
+const CODE_SYMBOLS: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const GROUP_SIZE: usize = 5;
+
+/// A custom puzzle to pass on to a friend as a short code.
+///
+/// The code carries the answer, a checksum of the word list it was set on
+/// and the game options. The answer is scrambled so it cannot be read off
+/// the code. Decoding checks all of these against the receiving game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Challenge {
+    pub answer: String,
+    pub max_rounds: usize,
+    pub hard_mode: bool,
+}
+
+impl Challenge {
+    /// A challenge for the game's current answer and options.
+    pub fn from_game(game: &Game) -> Challenge {
+        Challenge {
+            answer: game.answer(),
+            max_rounds: game.max_rounds(),
+            hard_mode: game.hard_mode(),
+        }
+    }
+    /// Code for this challenge on `game`'s word lists, e.g. `4J7QZ-M0C2R-...`.
+    pub fn encode(&self, game: &Game) -> Result<String> {
+        let alphabet = game.alphabet();
+        alphabet.check_word(&self.answer, game.word_length())?;
+        if !game.candidates.contains(&self.answer) {
+            return Err(Error::NotInWordList(self.answer.clone()));
+        }
+        if !(1..=u8::MAX as usize).contains(&self.max_rounds) {
+            return Err(Error::InvalidChallenge(format!(
+                "{} rounds cannot be encoded",
+                self.max_rounds
+            )));
+        }
+        let mut bytes = vec![CHALLENGE_VERSION];
+        bytes.extend_from_slice(&(game.candidates.checksum() as u32).to_le_bytes());
+        bytes.push(game.word_length() as u8);
+        bytes.push(self.max_rounds as u8);
+        bytes.push(self.hard_mode as u8);
+        bytes.extend(
+            alphabet
+                .encode(&self.answer)
+                .expect("answer checked against alphabet"),
+        );
+        bytes.push(check_byte(&bytes));
+        scramble(&mut bytes);
+        Ok(to_code(&bytes))
+    }
+    /// Read a code made by [`Challenge::encode`], checking it against the
+    /// word lists and word length of `game`.
+    pub fn decode(code: &str, game: &Game) -> Result<Challenge> {
+        let invalid = |reason: &str| Error::InvalidChallenge(reason.to_string());
+        let mut bytes = from_code(code).ok_or_else(|| invalid("unexpected character"))?;
+        if bytes.len() < 9 {
+            return Err(invalid("too short"));
+        }
+        scramble(&mut bytes);
+        let check = bytes.pop().expect("length checked above");
+        if check != check_byte(&bytes) {
+            return Err(invalid("mistyped or corrupt"));
+        }
+        if bytes[0] != CHALLENGE_VERSION {
+            return Err(invalid("unsupported version"));
+        }
+        if bytes[1..5] != (game.candidates.checksum() as u32).to_le_bytes() {
+            return Err(invalid("made with a different word list"));
+        }
+        let word_length = bytes[5] as usize;
+        if word_length != game.word_length() {
+            return Err(Error::LengthMismatch {
+                expected: game.word_length(),
+                found: word_length,
+            });
+        }
+        let symbols = &bytes[8..];
+        let alphabet = game.alphabet();
+        if symbols.len() != word_length || symbols.iter().any(|s| *s as usize >= alphabet.len()) {
+            return Err(invalid("answer does not fit the word list"));
+        }
+        let answer = alphabet.decode(symbols);
+        if !game.candidates.contains(&answer) {
+            return Err(Error::NotInWordList(answer));
+        }
+        if bytes[6] == 0 {
+            return Err(invalid("no rounds to play"));
+        }
+        Ok(Challenge {
+            answer,
+            max_rounds: bytes[6] as usize,
+            hard_mode: bytes[7] & 1 != 0,
+        })
+    }
+    /// Set `game` up to play this challenge.
+    pub fn apply(&self, game: &mut Game) -> Result<()> {
+        game.set_game_with_answer(self.answer.clone())?;
+        game.set_max_rounds(self.max_rounds)?;
+        game.set_hard_mode(self.hard_mode);
+        Ok(())
+    }
+}
+
+/// Catches mistyped codes before any field is trusted.
+fn check_byte(bytes: &[u8]) -> u8 {
+    bytes
+        .iter()
+        .fold(0x5a_u8, |acc, byte| acc.rotate_left(3) ^ byte)
+}
+
+/// XOR every byte but the version with a fixed xorshift keystream, so
+/// answers do not show through the code. Applying it twice undoes it.
+fn scramble(bytes: &mut [u8]) {
+    let mut state: u32 = 0x2f6b_9d31;
+    for byte in bytes.iter_mut().skip(1) {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        *byte ^= state as u8;
+    }
+}
+
+fn to_code(bytes: &[u8]) -> String {
+    let mut symbols = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for byte in bytes {
+        buffer = (buffer << 8) | *byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            symbols.push(CODE_SYMBOLS[((buffer >> bits) & 31) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        symbols.push(CODE_SYMBOLS[((buffer << (5 - bits)) & 31) as usize] as char);
+    }
+    symbols
+        .chunks(GROUP_SIZE)
+        .map(|group| group.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Bytes of a code, ignoring case, dashes and spaces, and reading the
+/// look-alikes I, L and O as 1, 1 and 0.
+fn from_code(code: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in code.chars().filter(|c| *c != '-' && !c.is_whitespace()) {
+        let c = match c.to_ascii_uppercase() {
+            'I' | 'L' => '1',
+            'O' => '0',
+            c => c,
+        };
+        let value = CODE_SYMBOLS.iter().position(|s| *s as char == c)? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wordlist::WordList;
+
+    #[test]
+    fn codes_survive_a_round_trip() {
+        let game = test_game("cigar");
+        let challenge = Challenge {
+            answer: "sissy".to_string(),
+            max_rounds: 4,
+            hard_mode: true,
+        };
+        let code = challenge.encode(&game).unwrap();
+        assert!(!code.to_lowercase().contains("sissy"));
+        assert_eq!(Challenge::decode(&code, &game).unwrap(), challenge);
+    }
+
+    #[test]
+    fn corrupted_codes_are_rejected() {
+        let game = test_game("cigar");
+        let code = Challenge::from_game(&game).encode(&game).unwrap();
+        let mut corrupted: Vec<char> = code.chars().collect();
+        corrupted[2] = if corrupted[2] == '7' { '8' } else { '7' };
+        let corrupted: String = corrupted.into_iter().collect();
+        assert!(matches!(
+            Challenge::decode(&corrupted, &game),
+            Err(Error::InvalidChallenge(_))
+        ));
+    }
+
+    #[test]
+    fn codes_are_tied_to_their_word_list() {
+        let game = test_game("cigar");
+        let code = Challenge::from_game(&game).encode(&game).unwrap();
+        let words = WordList::from_words(["cigar", "awake"], 5).unwrap();
+        let other = Game::from_word_lists(words.clone(), words, 0).unwrap();
+        assert!(matches!(
+            Challenge::decode(&code, &other),
+            Err(Error::InvalidChallenge(_))
+        ));
+    }
+}
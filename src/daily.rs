@@ -0,0 +1,143 @@
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Day of puzzle number 0. The answer list is kept in the original daily
+/// order, so puzzle `n` is `answers[n % answers.len()]`.
+pub const DAILY_EPOCH: Date = Date {
+    year: 2021,
+    month: 6,
+    day: 19,
+};
+
+/// A calendar date in the proleptic Gregorian calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Date> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Date { year, month, day })
+    }
+    /// Current date in UTC, so everyone shares the same daily word.
+    pub fn today() -> Date {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        Date::from_unix_days(seconds.div_euclid(86400))
+    }
+    /// Parse a `YYYY-MM-DD` date.
+    pub fn parse(text: &str) -> Option<Date> {
+        let mut parts = text.trim().splitn(3, '-');
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        Date::new(year, month, day)
+    }
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+    /// Days since 1970-01-01, negative before it.
+    pub fn unix_days(&self) -> i64 {
+        // Howard Hinnant's days_from_civil
+        let year = self.year as i64 - if self.month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let yoe = year - era * 400;
+        let month = self.month as i64;
+        let doy = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146097 + doe - 719468
+    }
+    pub fn from_unix_days(days: i64) -> Date {
+        // Howard Hinnant's civil_from_days
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z - era * 146097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+        Date { year, month, day }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Puzzle number of `date`, or `None` for days before [`DAILY_EPOCH`].
+pub fn puzzle_number(date: Date) -> Option<usize> {
+    let offset = date.unix_days() - DAILY_EPOCH.unix_days();
+    usize::try_from(offset).ok()
+}
+
+/// Date on which puzzle `number` is played.
+pub fn puzzle_date(number: usize) -> Date {
+    Date::from_unix_days(DAILY_EPOCH.unix_days() + number as i64)
+}
+
+/// Index into the answer list for the puzzle of `date`.
+pub fn daily_index(date: Date, answer_count: usize) -> Option<usize> {
+    assert!(answer_count > 0);
+    puzzle_number(date).map(|number| number % answer_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wordlist::WordList;
+
+    #[test]
+    fn epoch_is_puzzle_zero() {
+        let date = Date::parse("2021-06-19").unwrap();
+        assert_eq!(puzzle_number(date), Some(0));
+        let answers = WordList::embedded_answers();
+        let index = daily_index(date, answers.len()).unwrap();
+        assert_eq!(answers.words()[index], "cigar");
+    }
+
+    #[test]
+    fn no_puzzle_before_the_epoch() {
+        let date = Date::new(2021, 6, 18).unwrap();
+        assert_eq!(puzzle_number(date), None);
+    }
+
+    #[test]
+    fn dates_round_trip_through_puzzle_numbers() {
+        let date = Date::parse("2024-02-29").unwrap();
+        let number = puzzle_number(date).unwrap();
+        assert_eq!(puzzle_date(number), date);
+    }
+
+    #[test]
+    fn malformed_dates_do_not_parse() {
+        assert_eq!(Date::parse("2023-02-29"), None);
+        assert_eq!(Date::parse("2023-13-01"), None);
+        assert_eq!(Date::parse("yesterday"), None);
+    }
+}
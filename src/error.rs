@@ -0,0 +1,121 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Errors raised while loading word lists and data files.
+#[derive(Debug)]
+pub enum Error {
+    /// A data file could not be opened or read.
+    MissingDataFile { path: PathBuf, source: io::Error },
+    /// A JSON word list is not an array of strings.
+    MalformedJson {
+        path: Option<PathBuf>,
+        source: serde_json::Error,
+    },
+    /// A data file parsed as JSON but does not have the expected shape.
+    MalformedData {
+        path: Option<PathBuf>,
+        reason: String,
+    },
+    /// A file could not be written.
+    WriteFailed { path: PathBuf, source: io::Error },
+    /// A word list holds no words at all.
+    EmptyWordList { path: Option<PathBuf> },
+    /// Two word lists meant to be used together have different word lengths.
+    LengthMismatch { expected: usize, found: usize },
+    /// Two word lists meant to be used together use different alphabets.
+    AlphabetMismatch { expected: String, found: String },
+    /// An alphabet name that is neither built in nor a `symbols:` list.
+    UnknownAlphabet(String),
+    /// An alphabet must hold 1 to 256 symbols.
+    InvalidAlphabetSize(usize),
+    /// Word lengths outside `MIN_WORD_LENGTH..=MAX_WORD_LENGTH` are not supported.
+    UnsupportedWordLength(usize),
+    /// A word does not have the game's word length.
+    WrongLength { word: String, expected: usize },
+    /// A word contains a character outside its word list's alphabet.
+    InvalidCharacter { word: String, character: char },
+    /// A word is not in the word list it was checked against.
+    NotInWordList(String),
+    /// A challenge code is corrupt or was made for other word lists.
+    InvalidChallenge(String),
+    /// A search query could not be parsed.
+    InvalidQuery(String),
+    /// A number of lies per row that the game cannot tell.
+    InvalidLies(String),
+    /// A game cannot be played in this many rounds.
+    InvalidRounds(usize),
+    /// A multi-board game cannot be set up with this many boards.
+    InvalidBoards(usize),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::MissingDataFile { path, source } => {
+                write!(f, "cannot read {}: {}", path.display(), source)
+            }
+            Error::MalformedJson {
+                path: Some(path),
+                source,
+            } => write!(f, "malformed JSON in {}: {}", path.display(), source),
+            Error::MalformedJson { path: None, source } => {
+                write!(f, "malformed JSON word list: {}", source)
+            }
+            Error::MalformedData {
+                path: Some(path),
+                reason,
+            } => write!(f, "malformed data in {}: {}", path.display(), reason),
+            Error::MalformedData { path: None, reason } => write!(f, "malformed data: {}", reason),
+            Error::WriteFailed { path, source } => {
+                write!(f, "cannot write {}: {}", path.display(), source)
+            }
+            Error::EmptyWordList { path: Some(path) } => {
+                write!(f, "{} holds no words", path.display())
+            }
+            Error::EmptyWordList { path: None } => write!(f, "word list holds no words"),
+            Error::LengthMismatch { expected, found } => write!(
+                f,
+                "word lists disagree on word length: {} and {}",
+                expected, found
+            ),
+            Error::AlphabetMismatch { expected, found } => write!(
+                f,
+                "word lists disagree on alphabet: {} and {}",
+                expected, found
+            ),
+            Error::UnknownAlphabet(name) => write!(f, "unknown alphabet {:?}", name),
+            Error::InvalidAlphabetSize(size) => {
+                write!(f, "alphabets hold 1 to 256 symbols, not {}", size)
+            }
+            Error::UnsupportedWordLength(length) => {
+                write!(f, "unsupported word length {}", length)
+            }
+            Error::WrongLength { word, expected } => {
+                write!(f, "{:?} is not {} letters long", word, expected)
+            }
+            Error::InvalidCharacter { word, character } => {
+                write!(f, "{:?} contains invalid character {:?}", word, character)
+            }
+            Error::NotInWordList(word) => write!(f, "{:?} is not in the word list", word),
+            Error::InvalidChallenge(reason) => write!(f, "invalid challenge code: {}", reason),
+            Error::InvalidQuery(reason) => write!(f, "invalid query: {}", reason),
+            Error::InvalidLies(reason) => write!(f, "invalid lies: {}", reason),
+            Error::InvalidRounds(rounds) => write!(f, "cannot play a game of {} rounds", rounds),
+            Error::InvalidBoards(boards) => write!(f, "cannot play {} boards at once", boards),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::MissingDataFile { source, .. } => Some(source),
+            Error::MalformedJson { source, .. } => Some(source),
+            Error::WriteFailed { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use super::game::*;
+use super::share::emoji_state;
+use std::fmt;
+
+/// How much of a guess's grading the player gets to see.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FeedbackKind {
+    /// A colour per tile, as in Wordle.
+    #[default]
+    Tiles,
+    /// Only the number of letters shared with the answer, as in Jotto.
+    Jotto,
+    /// The numbers of correct and misplaced letters without their
+    /// positions, as in Mastermind.
+    Mastermind,
+}
+
+impl FeedbackKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FeedbackKind::Tiles => "tiles",
+            FeedbackKind::Jotto => "jotto",
+            FeedbackKind::Mastermind => "mastermind",
+        }
+    }
+    pub fn parse(name: &str) -> Option<FeedbackKind> {
+        match name {
+            "tiles" => Some(FeedbackKind::Tiles),
+            "jotto" => Some(FeedbackKind::Jotto),
+            "mastermind" => Some(FeedbackKind::Mastermind),
+            _ => None,
+        }
+    }
+    /// The part of a full grading this kind reveals.
+    pub fn feedback(&self, one_match: &Match) -> Feedback {
+        let count = |wanted: GuessState| {
+            one_match
+                .states
+                .iter()
+                .filter(|state| **state == wanted)
+                .count()
+        };
+        match self {
+            FeedbackKind::Tiles => Feedback::Tiles(one_match.clone()),
+            // Grading counts repeated letters, so greens and yellows together
+            // are the letters the two words share
+            FeedbackKind::Jotto => {
+                Feedback::Jotto(count(GuessState::Correct) + count(GuessState::Misplace))
+            }
+            FeedbackKind::Mastermind => Feedback::Mastermind {
+                correct: count(GuessState::Correct),
+                misplaced: count(GuessState::Misplace),
+            },
+        }
+    }
+    /// Number of distinct [`Feedback::index`] values for words of `word_length`.
+    pub fn slots(&self, word_length: usize) -> usize {
+        match self {
+            FeedbackKind::Tiles => 3usize.pow(word_length as u32),
+            FeedbackKind::Jotto => word_length + 1,
+            FeedbackKind::Mastermind => (word_length + 1) * (word_length + 1),
+        }
+    }
+}
+
+/// Feedback on one guess, as revealed by a [`FeedbackKind`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Feedback {
+    Tiles(Match),
+    Jotto(usize),
+    Mastermind { correct: usize, misplaced: usize },
+}
+
+impl Feedback {
+    pub fn kind(&self) -> FeedbackKind {
+        match self {
+            Feedback::Tiles(_) => FeedbackKind::Tiles,
+            Feedback::Jotto(_) => FeedbackKind::Jotto,
+            Feedback::Mastermind { .. } => FeedbackKind::Mastermind,
+        }
+    }
+    /// Slot of this feedback among the [`FeedbackKind::slots`] of its kind.
+    pub fn index(&self, word_length: usize) -> usize {
+        match self {
+            Feedback::Tiles(one_match) => one_match.pattern_index(),
+            Feedback::Jotto(shared) => *shared,
+            Feedback::Mastermind { correct, misplaced } => correct * (word_length + 1) + misplaced,
+        }
+    }
+    /// Tile colours to show for this feedback. Counts are shown as sorted
+    /// pegs: correct first, then misplaced, then wrong. Jotto's shared
+    /// letters show as misplaced.
+    pub fn states(&self, word_length: usize) -> Vec<GuessState> {
+        let pegs = |correct: usize, misplaced: usize| {
+            let mut states = vec![GuessState::Correct; correct];
+            states.resize(correct + misplaced, GuessState::Misplace);
+            states.resize(word_length, GuessState::Wrong);
+            states
+        };
+        match self {
+            Feedback::Tiles(one_match) => one_match.states.clone(),
+            Feedback::Jotto(shared) => pegs(0, *shared),
+            Feedback::Mastermind { correct, misplaced } => pegs(*correct, *misplaced),
+        }
+    }
+}
+
+impl fmt::Display for Feedback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Feedback::Tiles(one_match) => {
+                for state in one_match.states.iter() {
+                    let c = match state {
+                        GuessState::Correct => 'g',
+                        GuessState::Misplace => 'y',
+                        GuessState::Wrong => 'b',
+                    };
+                    write!(f, "{}", c)?;
+                }
+                Ok(())
+            }
+            Feedback::Jotto(shared) => write!(f, "{} shared", shared),
+            Feedback::Mastermind { correct, misplaced } => {
+                write!(f, "{} correct, {} misplaced", correct, misplaced)
+            }
+        }
+    }
+}
+
+/// Read tile feedback as typed by a player: letters (`gybbb`), digits
+/// (`21000`, 2 for correct) or emoji from any share set. Spaces are ignored.
+pub fn parse_tiles(text: &str) -> Option<Match> {
+    let states = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '\u{fe0f}')
+        .map(|c| match c.to_ascii_lowercase() {
+            'g' | '2' => Some(GuessState::Correct),
+            'y' | '1' => Some(GuessState::Misplace),
+            'b' | '0' => Some(GuessState::Wrong),
+            c => emoji_state(c),
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if states.is_empty() {
+        return None;
+    }
+    Some(Match { states })
+}
@@ -1,7 +1,23 @@
-use std::collections::BTreeSet;
-use std::io::prelude::*;
+use super::alphabet::Alphabet;
+use super::daily::{self, Date};
+use super::error::{Error, Result};
+use super::feedback::{Feedback, FeedbackKind};
+use super::hint::{Hint, HintCosts, HintKind};
+use super::keyboard::Keyboard;
+use super::nerdle;
+use super::solver::Solver;
+use super::transcript::{Transcript, TranscriptEntry};
+use super::wordlist::WordList;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
 use std::sync::Arc;
 
+pub const MIN_WORD_LENGTH: usize = 4;
+pub const MAX_WORD_LENGTH: usize = 11;
+
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
 pub enum GuessState {
     Wrong,
@@ -9,98 +25,256 @@ pub enum GuessState {
     Correct,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Game {
     answer: String,
-    answer_index: usize,
-    pub answers: Vec<String>,
-    pub candidates: Vec<String>,
+    /// Position of the answer in `answers`, `None` for an answer off the list.
+    answer_index: Option<usize>,
+    /// Words the answer is drawn from.
+    pub answers: WordList,
+    /// Every word accepted as a guess, answers included.
+    pub candidates: WordList,
+    mode: GameMode,
+    seed: u64,
+    word_length: usize,
+    max_rounds: usize,
     round: usize,
     pub state: GameState,
-}
-impl Default for Game {
-    fn default() -> Self {
-        Self::new()
-    }
+    hard_mode: bool,
+    /// Tiles per row given false feedback, as in Fibble. Zero for honest games.
+    lies: usize,
+    feedback_kind: FeedbackKind,
+    hint_costs: HintCosts,
+    /// Guesses must be true equations, as in Nerdle.
+    nerdle: bool,
+    transcript: Transcript,
 }
 impl Game {
-    pub fn new() -> Game {
-        let mut answer_strings = String::new();
-        {
-            let mut answer_file = std::fs::File::open("./data/answer").unwrap();
-            answer_file.read_to_string(&mut answer_strings).unwrap();
+    pub fn new() -> Result<Game> {
+        Self::with_word_length(5)
+    }
+    /// Start a game whose words are `word_length` letters long, loading the
+    /// answer and candidate lists for that length. Only five-letter lists
+    /// ship with the game; see [`available_word_lengths`] for the others.
+    pub fn with_word_length(word_length: usize) -> Result<Game> {
+        Self::with_seed(word_length, rand::random())
+    }
+    /// Like [`Game::with_word_length`], but the answer is drawn from `seed`
+    /// so the same seed always yields the same game.
+    pub fn with_seed(word_length: usize, seed: u64) -> Result<Game> {
+        if !(MIN_WORD_LENGTH..=MAX_WORD_LENGTH).contains(&word_length) {
+            return Err(Error::UnsupportedWordLength(word_length));
         }
-        let answers: Vec<String> = serde_json::from_str(&answer_strings).unwrap();
-
-        let mut index: usize = rand::random();
-        index %= answers.len();
-
-        let mut candidate_strings = String::new();
-        {
-            let mut candidate_file = std::fs::File::open("./data/candidate").unwrap();
-            candidate_file
-                .read_to_string(&mut candidate_strings)
-                .unwrap();
+        let (answer_path, candidate_path) = data_paths(word_length);
+        let answers = WordList::load(answer_path, word_length)?;
+        let allowed = WordList::load(candidate_path, word_length)?;
+        Self::from_word_lists(answers, allowed, seed)
+    }
+    /// Start an equation puzzle in the style of Nerdle over the generated
+    /// equations of [`nerdle::NERDLE_LENGTH`] symbols.
+    pub fn nerdle(seed: u64) -> Result<Game> {
+        let (answers, equations) = nerdle::word_lists(nerdle::NERDLE_LENGTH);
+        let mut game = Self::from_word_lists(answers, equations, seed)?;
+        game.nerdle = true;
+        Ok(game)
+    }
+    /// Start a game over custom lists. `allowed` holds the extra words
+    /// accepted as guesses; every answer is accepted as well.
+    pub fn from_word_lists(answers: WordList, allowed: WordList, seed: u64) -> Result<Game> {
+        let word_length = answers.word_length();
+        if !(MIN_WORD_LENGTH..=MAX_WORD_LENGTH).contains(&word_length) {
+            return Err(Error::UnsupportedWordLength(word_length));
         }
+        let candidates = allowed.union(&answers)?;
 
-        let mut candidate_vec: Vec<String> = serde_json::from_str(&candidate_strings).unwrap();
-        let answer = answers[index].clone();
+        let index = seeded_index(seed, answers.len());
+        let answer = answers.words()[index].clone();
 
-        candidate_vec.append(&mut (answers.clone()));
-        Game {
+        let mut game = Game {
             answer,
-            answer_index: index,
+            answer_index: Some(index),
             answers,
-            candidates: candidate_vec,
+            candidates,
+            mode: GameMode::Random,
+            seed,
+            word_length,
+            max_rounds: 6,
             round: 0,
             state: GameState::On,
+            hard_mode: false,
+            lies: 0,
+            feedback_kind: FeedbackKind::Tiles,
+            hint_costs: HintCosts::default(),
+            nerdle: false,
+            transcript: Transcript {
+                answer: String::new(),
+                mode: GameMode::Random,
+                seed,
+                word_length,
+                hard_mode: false,
+                lies: 0,
+                feedback_kind: FeedbackKind::Tiles,
+                max_rounds: 6,
+                started_at: 0,
+                entries: Vec::new(),
+                hints: Vec::new(),
+            },
+        };
+        game.restart(GameMode::Random);
+        Ok(game)
+    }
+    /// Begin a fresh round of play on the current answer.
+    fn restart(&mut self, mode: GameMode) {
+        self.mode = mode;
+        self.round = 0;
+        self.state = GameState::On;
+        self.transcript = Transcript::new(self);
+    }
+    /// Set up the game recorded in `transcript`, ready to replay its guesses.
+    pub(crate) fn start_transcript(&mut self, transcript: &Transcript) -> Result<()> {
+        if transcript.word_length != self.word_length {
+            return Err(Error::LengthMismatch {
+                expected: self.word_length,
+                found: transcript.word_length,
+            });
+        }
+        self.alphabet()
+            .check_word(&transcript.answer, self.word_length)?;
+        check_lies(transcript.lies, self.word_length, transcript.feedback_kind)?;
+        self.answer_index = self.answers.position(&transcript.answer);
+        self.answer = transcript.answer.clone();
+        self.seed = transcript.seed;
+        self.hard_mode = transcript.hard_mode;
+        self.lies = transcript.lies;
+        self.feedback_kind = transcript.feedback_kind;
+        if transcript.max_rounds == 0 {
+            return Err(Error::InvalidRounds(0));
         }
+        self.max_rounds = transcript.max_rounds;
+        self.restart(transcript.mode);
+        Ok(())
     }
     pub fn set_game_with_answer_index(&mut self, index: usize) {
         assert!(index < self.answers.len());
-        self.answer_index = index;
-        self.answer = self.answers[self.answer_index].clone();
-        self.round = 0;
-        self.state = GameState::On;
+        self.answer_index = Some(index);
+        self.answer = self.answers.words()[index].clone();
+        self.restart(GameMode::Custom);
     }
-    pub fn set_game_with_answer(&mut self, answer: String) {
-        self.answer_index = 0;
+    pub fn set_game_with_answer(&mut self, answer: String) -> Result<()> {
+        self.alphabet().check_word(&answer, self.word_length)?;
+        self.answer_index = self.answers.position(&answer);
         self.answer = answer;
-        self.round = 0;
-        self.state = GameState::On;
+        self.restart(GameMode::Custom);
+        Ok(())
     }
+    /// Answer index of the daily puzzle on `date`, past or future.
+    /// `None` for dates before [`daily::DAILY_EPOCH`].
+    pub fn daily_answer_index(&self, date: Date) -> Option<usize> {
+        daily::daily_index(date, self.answers.len())
+    }
+    /// Start the daily puzzle of `date` and return its puzzle number.
+    pub fn set_daily(&mut self, date: Date) -> Option<usize> {
+        let index = self.daily_answer_index(date)?;
+        let number = daily::puzzle_number(date)?;
+        self.answer_index = Some(index);
+        self.answer = self.answers.words()[index].clone();
+        self.restart(GameMode::Daily(number));
+        Some(number)
+    }
+    /// Feedback for a guess. In a lying game the tiles of a wrong guess are
+    /// falsified as picked by [`lie_about`] for this seed and round.
     pub fn grade_guess(&self, guess: &Guess) -> Match {
-        let mut one_match = Match::new();
-        // Correct pass
-        let word = &guess.state;
-        let mut char_set: BTreeSet<u8> = BTreeSet::new();
-        let answer_bytes = self.answer.as_bytes();
-        let word_bytes = word.as_bytes();
-        char_set.extend(answer_bytes.iter());
-
-        for i in 0..5 {
-            if word_bytes[i] == answer_bytes[i] {
-                one_match.states[i] = GuessState::Correct;
-            } else if char_set.contains(&word_bytes[i]) {
-                one_match.states[i] = GuessState::Misplace;
-            } else {
-                one_match.states[i] = GuessState::Wrong;
-            }
+        let truth = grade(&guess.state, &self.answer);
+        if self.lies == 0 || guess.state == self.answer {
+            return truth;
         }
-        one_match
+        lie_about(&truth, self.lies, self.seed, self.round)
     }
     pub fn check_valid_guess(&self, guess: &Guess) -> bool {
+        self.validate_guess(guess).is_ok()
+    }
+    /// Check a guess against the word list and, in hard mode, against every
+    /// hint revealed so far. Hard mode is only enforced with honest tile
+    /// feedback, the only kind that pins letters to positions.
+    pub fn validate_guess(&self, guess: &Guess) -> std::result::Result<(), GuessError> {
+        if self.state != GameState::On {
+            return Err(GuessError::GameFinished);
+        }
         let word = &guess.state;
-        self.candidates.iter().any(|i| *i == *word)
+        if !self.candidates.contains(word) {
+            if self.nerdle && !nerdle::is_valid_equation(word) {
+                return Err(GuessError::NotAnEquation);
+            }
+            return Err(GuessError::NotInWordList);
+        }
+        if self.hard_mode && self.lies == 0 && self.feedback_kind == FeedbackKind::Tiles {
+            self.check_hard_mode(word)?;
+        }
+        Ok(())
     }
-    pub fn progress_game(&mut self, one_match: Arc<Match>) {
-        if one_match.is_correct() {
+    fn check_hard_mode(&self, word: &str) -> std::result::Result<(), GuessError> {
+        let word_chars: Vec<char> = word.chars().collect();
+        let mut required: BTreeMap<char, usize> = BTreeMap::new();
+        for entry in self.transcript.entries.iter() {
+            let prev_chars: Vec<char> = entry.guess.chars().collect();
+            let one_match = &entry.feedback;
+            let mut revealed: BTreeMap<char, usize> = BTreeMap::new();
+            for i in 0..self.word_length {
+                match one_match.states[i] {
+                    GuessState::Correct => {
+                        if word_chars[i] != prev_chars[i] {
+                            return Err(GuessError::MissingCorrect {
+                                position: i,
+                                letter: prev_chars[i],
+                            });
+                        }
+                        *revealed.entry(prev_chars[i]).or_default() += 1;
+                    }
+                    GuessState::Misplace => *revealed.entry(prev_chars[i]).or_default() += 1,
+                    GuessState::Wrong => {}
+                }
+            }
+            for (letter, seen) in revealed {
+                let need = required.entry(letter).or_default();
+                *need = (*need).max(seen);
+            }
+        }
+        for (letter, need) in required {
+            let count = word_chars.iter().filter(|c| **c == letter).count();
+            if count < need {
+                return Err(GuessError::MissingPresent {
+                    letter,
+                    count: need,
+                });
+            }
+        }
+        Ok(())
+    }
+    pub fn progress_game(&mut self, guess: &Guess, one_match: Arc<Match>) {
+        self.transcript.record(guess, one_match.clone());
+        if guess.state == self.answer {
             self.state = GameState::Correct;
         } else {
-            self.state = GameState::On;
             self.inc_round();
+            self.state = if self.round >= self.max_rounds {
+                GameState::Over
+            } else {
+                GameState::On
+            };
         }
     }
+    /// Take back the last graded guess, returning its transcript entry.
+    /// The round and state go back to what they were before that guess, and
+    /// hints taken after it are dropped along with their cost.
+    pub fn undo(&mut self) -> Option<TranscriptEntry> {
+        let entry = self.transcript.entries.pop()?;
+        // Every guess still recorded missed, so each one used up a round
+        self.round = self.transcript.entries.len();
+        self.state = GameState::On;
+        let round = self.round;
+        self.transcript.hints.retain(|hint| hint.round <= round);
+        Some(entry)
+    }
     pub fn round(&self) -> usize {
         self.round
     }
@@ -110,44 +284,337 @@ impl Game {
     pub fn answer(&self) -> String {
         self.answer.clone()
     }
+    /// Position of the answer in [`Game::answers`], `None` when a custom or
+    /// replayed answer is not on the list.
+    pub fn answer_index(&self) -> Option<usize> {
+        self.answer_index
+    }
+    /// Seed the current random answer was drawn from.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+    pub fn mode(&self) -> GameMode {
+        self.mode
+    }
+    pub fn word_length(&self) -> usize {
+        self.word_length
+    }
+    /// Whether this is an equation puzzle started by [`Game::nerdle`].
+    pub fn is_nerdle(&self) -> bool {
+        self.nerdle
+    }
+    /// Alphabet the words of this game are spelled in.
+    pub fn alphabet(&self) -> &Alphabet {
+        self.candidates.alphabet()
+    }
+    pub fn max_rounds(&self) -> usize {
+        self.max_rounds
+    }
+    /// Number of guesses allowed before the game moves to `GameState::Over`.
+    /// At least one round is needed.
+    pub fn set_max_rounds(&mut self, max_rounds: usize) -> Result<()> {
+        if max_rounds == 0 {
+            return Err(Error::InvalidRounds(max_rounds));
+        }
+        self.max_rounds = max_rounds;
+        self.transcript.max_rounds = max_rounds;
+        Ok(())
+    }
+    pub fn hard_mode(&self) -> bool {
+        self.hard_mode
+    }
+    pub fn set_hard_mode(&mut self, hard_mode: bool) {
+        self.hard_mode = hard_mode;
+        self.transcript.hard_mode = hard_mode;
+    }
+    /// Record of the current game: its setup and every graded guess.
+    pub fn transcript(&self) -> &Transcript {
+        &self.transcript
+    }
+    pub fn lies(&self) -> usize {
+        self.lies
+    }
+    /// Falsify `lies` tiles of every wrong guess's feedback from now on.
+    /// Fewer lies than letters are allowed, and only with tile feedback.
+    pub fn set_lies(&mut self, lies: usize) -> Result<()> {
+        check_lies(lies, self.word_length, self.feedback_kind)?;
+        self.lies = lies;
+        self.transcript.lies = lies;
+        Ok(())
+    }
+    pub fn feedback_kind(&self) -> FeedbackKind {
+        self.feedback_kind
+    }
+    /// Choose how much of each grading the player sees. Games with lies
+    /// must keep tile feedback.
+    pub fn set_feedback_kind(&mut self, feedback_kind: FeedbackKind) -> Result<()> {
+        check_lies(self.lies, self.word_length, feedback_kind)?;
+        self.feedback_kind = feedback_kind;
+        self.transcript.feedback_kind = feedback_kind;
+        Ok(())
+    }
+    /// The part of a grading from [`Game::grade_guess`] shown to the player.
+    pub fn feedback(&self, one_match: &Match) -> Feedback {
+        self.feedback_kind.feedback(one_match)
+    }
+    pub fn hint_costs(&self) -> HintCosts {
+        self.hint_costs
+    }
+    pub fn set_hint_costs(&mut self, hint_costs: HintCosts) {
+        self.hint_costs = hint_costs;
+    }
+    /// Total penalty points spent on hints this game.
+    pub fn hint_cost(&self) -> u32 {
+        self.transcript.hints.iter().map(|entry| entry.cost).sum()
+    }
+    /// Ask for a hint, charging its cost and recording it in the transcript.
+    /// `None` once the game is finished, or when every letter is known.
+    pub fn hint(&mut self, kind: HintKind) -> Option<Hint> {
+        let hint = self.find_hint(kind)?;
+        self.record_hint(kind, hint.clone());
+        Some(hint)
+    }
+    /// Work out a hint without charging for it. Solver-backed hints can take
+    /// a while on large lists, so this can run on a copy of the game away
+    /// from the interface, with [`Game::record_hint`] charging for it later.
+    pub fn find_hint(&self, kind: HintKind) -> Option<Hint> {
+        if self.state != GameState::On {
+            return None;
+        }
+        let hint = match kind {
+            HintKind::RevealLetter => {
+                let keyboard = self.keyboard();
+                let revealed: Vec<usize> = self
+                    .transcript
+                    .hints
+                    .iter()
+                    .filter_map(|entry| match entry.hint {
+                        Hint::RevealLetter { position, .. } => Some(position),
+                        _ => None,
+                    })
+                    .collect();
+                let position = (0..self.word_length).find(|position| {
+                    !revealed.contains(position)
+                        && !keyboard
+                            .iter()
+                            .any(|(_, info)| info.known_at.contains(position))
+                })?;
+                Hint::RevealLetter {
+                    position,
+                    letter: self.answer.chars().nth(position)?,
+                }
+            }
+            HintKind::RemainingCount => Hint::RemainingCount(self.solver().remaining()),
+            HintKind::BestGuess => {
+                Hint::BestGuess(self.solver().new_guess(self.round as u8).0.state)
+            }
+        };
+        Some(hint)
+    }
+    /// Charge for a hint of `kind` found by [`Game::find_hint`] this round.
+    pub fn record_hint(&mut self, kind: HintKind, hint: Hint) {
+        self.transcript
+            .record_hint(hint, self.hint_costs.cost(kind), self.round);
+    }
+    /// Solver over the possible answers, narrowed by every graded guess.
+    fn solver(&self) -> Solver {
+        let mut solver = Solver::new(&self.answers);
+        solver.set_lies(self.lies);
+        solver.set_feedback_kind(self.feedback_kind);
+        for entry in self.transcript.entries.iter() {
+            solver
+                .observe(&entry.guess, self.feedback(&entry.feedback))
+                .expect("recorded guesses are valid words");
+        }
+        solver
+    }
+    /// Per-letter knowledge from the guesses graded so far.
+    pub fn keyboard(&self) -> Keyboard {
+        Keyboard::from_game(self)
+    }
+    /// Start the next random game. Its seed is derived from the current one,
+    /// so a sequence of games is reproducible from the first seed.
     pub fn reset(&mut self) {
-        let mut index: usize = rand::random();
-        index %= self.answers.len();
+        let seed = ChaCha8Rng::seed_from_u64(self.seed).gen();
+        self.reset_with_seed(seed);
+    }
+    pub fn reset_with_seed(&mut self, seed: u64) {
+        let index = seeded_index(seed, self.answers.len());
 
-        self.answer = self.answers[index].clone();
+        self.seed = seed;
+        self.answer_index = Some(index);
+        self.answer = self.answers.words()[index].clone();
+        self.restart(GameMode::Random);
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct Match {
-    pub states: [GuessState; 5],
+/// Word lengths [`Game::with_word_length`] can start a game with: those
+/// whose answer and candidate lists are both under `./data`.
+pub fn available_word_lengths() -> Vec<usize> {
+    (MIN_WORD_LENGTH..=MAX_WORD_LENGTH)
+        .filter(|length| {
+            let (answer_path, candidate_path) = data_paths(*length);
+            Path::new(&answer_path).is_file() && Path::new(&candidate_path).is_file()
+        })
+        .collect()
 }
-impl Default for Match {
-    fn default() -> Self {
-        Self::new()
+
+/// Answer and candidate list paths for a word length. Five-letter lists keep
+/// their original names, other lengths carry the length as a suffix. Only
+/// the five-letter lists are shipped; lists for other lengths, in any format
+/// [`WordList::load`] reads, can be added as `answer_N` and `candidate_N`.
+fn data_paths(word_length: usize) -> (String, String) {
+    if word_length == 5 {
+        ("./data/answer".to_string(), "./data/candidate".to_string())
+    } else {
+        (
+            format!("./data/answer_{}", word_length),
+            format!("./data/candidate_{}", word_length),
+        )
+    }
+}
+
+/// Lies must leave at least one honest tile, and only tiles can lie.
+fn check_lies(lies: usize, word_length: usize, feedback_kind: FeedbackKind) -> Result<()> {
+    if lies >= word_length {
+        return Err(Error::InvalidLies(format!(
+            "{} lies in words of {} letters",
+            lies, word_length
+        )));
+    }
+    if lies > 0 && feedback_kind != FeedbackKind::Tiles {
+        return Err(Error::InvalidLies(format!(
+            "{} feedback cannot lie",
+            feedback_kind.name()
+        )));
     }
+    Ok(())
+}
+
+fn seeded_index(seed: u64, len: usize) -> usize {
+    ChaCha8Rng::seed_from_u64(seed).gen_range(0..len)
+}
+
+/// Feedback with `lies` tiles of `truth` changed to one of the other two
+/// states. Positions and states are drawn from `seed` and `round`, so a game
+/// replays with the same lies. Never turns a wrong guess all green. At most
+/// every tile lies.
+pub fn lie_about(truth: &Match, lies: usize, seed: u64, round: usize) -> Match {
+    let mut rng =
+        ChaCha8Rng::seed_from_u64(seed ^ (round as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    let lies = lies.min(truth.states.len());
+    let positions = rand::seq::index::sample(&mut rng, truth.states.len(), lies);
+    let mut states = truth.states.clone();
+    for position in positions.iter() {
+        let others: Vec<GuessState> =
+            [GuessState::Wrong, GuessState::Misplace, GuessState::Correct]
+                .into_iter()
+                .filter(|state| *state != truth.states[position])
+                .collect();
+        states[position] = others[rng.gen_range(0..2)];
+    }
+    let mut lied = Match { states };
+    if lied.is_correct() {
+        // The only tile told Correct was a lie; tell a different one
+        let position = positions.index(0);
+        lied.states[position] = match truth.states[position] {
+            GuessState::Wrong => GuessState::Misplace,
+            _ => GuessState::Wrong,
+        };
+    }
+    lied
+}
+
+/// Grade `guess` against `answer` following the Wordle rules.
+///
+/// Greens are assigned first; each remaining guess letter is then marked
+/// `Misplace` only while the answer still has unmatched copies of it, so
+/// repeated letters never receive more hints than the answer holds.
+pub fn grade(guess: &str, answer: &str) -> Match {
+    let guess: Vec<char> = guess.chars().collect();
+    let answer: Vec<char> = answer.chars().collect();
+    grade_symbols(&guess, &answer)
+}
+
+/// [`grade`] over words already split into symbols, such as the symbol
+/// indices of a [`WordList`].
+pub fn grade_symbols<T: PartialEq>(guess: &[T], answer: &[T]) -> Match {
+    let mut one_match = Match::new(answer.len());
+    grade_into(guess, answer, &mut one_match.states);
+    one_match
+}
+
+/// [`grade_symbols`] into `states`, one per symbol, so many pairs can be
+/// graded without allocating.
+pub fn grade_into<T: PartialEq>(guess: &[T], answer: &[T], states: &mut [GuessState]) {
+    assert_eq!(guess.len(), answer.len());
+    assert_eq!(states.len(), answer.len());
+    states.fill(GuessState::Wrong);
+    let mut used = [false; MAX_WORD_LENGTH];
+
+    // Correct pass
+    for i in 0..answer.len() {
+        if guess[i] == answer[i] {
+            states[i] = GuessState::Correct;
+            used[i] = true;
+        }
+    }
+    // Misplace pass, each answer symbol backs at most one hint
+    for (symbol, state) in guess.iter().zip(states.iter_mut()) {
+        if *state == GuessState::Correct {
+            continue;
+        }
+        if let Some(j) = (0..answer.len()).find(|j| !used[*j] && answer[*j] == *symbol) {
+            used[j] = true;
+            *state = GuessState::Misplace;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Match {
+    pub states: Vec<GuessState>,
 }
 impl Match {
     pub fn is_correct(&self) -> bool {
-        self.states[0] == GuessState::Correct
-            && self.states[1] == GuessState::Correct
-            && self.states[2] == GuessState::Correct
-            && self.states[3] == GuessState::Correct
-            && self.states[4] == GuessState::Correct
+        self.states
+            .iter()
+            .all(|state| *state == GuessState::Correct)
+    }
+    /// Base-3 index of the pattern, `Wrong = 0`, `Misplace = 1`, `Correct = 2`.
+    pub fn pattern_index(&self) -> usize {
+        self.states
+            .iter()
+            .fold(0, |index, state| index * 3 + *state as usize)
+    }
+    /// Inverse of [`Match::pattern_index`].
+    pub fn from_pattern_index(mut index: usize, word_length: usize) -> Match {
+        let mut one_match = Match::new(word_length);
+        for state in one_match.states.iter_mut().rev() {
+            *state = match index % 3 {
+                2 => GuessState::Correct,
+                1 => GuessState::Misplace,
+                _ => GuessState::Wrong,
+            };
+            index /= 3;
+        }
+        one_match
     }
-    pub fn new() -> Match {
+    pub fn new(word_length: usize) -> Match {
         Match {
-            states: [
-                GuessState::Wrong,
-                GuessState::Wrong,
-                GuessState::Wrong,
-                GuessState::Wrong,
-                GuessState::Wrong,
-            ],
+            states: vec![GuessState::Wrong; word_length],
         }
     }
 }
-#[derive(PartialEq, Debug)]
+/// How the answer of the current game was chosen.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum GameMode {
+    Random,
+    /// Daily puzzle with its puzzle number.
+    Daily(usize),
+    Custom,
+}
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
 pub enum GameState {
     On,
     ReadyForCheck,
@@ -158,3 +625,252 @@ pub enum GameState {
 pub struct Guess {
     pub state: String,
 }
+
+/// Reason a guess was refused by [`Game::validate_guess`].
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum GuessError {
+    /// The game is already won or out of rounds.
+    GameFinished,
+    NotInWordList,
+    /// Equation puzzles: the guess does not compute.
+    NotAnEquation,
+    /// Hard mode: a green letter was not kept in place.
+    MissingCorrect {
+        position: usize,
+        letter: char,
+    },
+    /// Hard mode: a revealed letter was left out.
+    MissingPresent {
+        letter: char,
+        count: usize,
+    },
+}
+impl fmt::Display for GuessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GuessError::GameFinished => write!(f, "Game is over"),
+            GuessError::NotInWordList => write!(f, "Not in word list"),
+            GuessError::NotAnEquation => write!(f, "Not a valid equation"),
+            GuessError::MissingCorrect { position, letter } => write!(
+                f,
+                "{} letter must be {}",
+                ordinal(position + 1),
+                letter.to_uppercase()
+            ),
+            GuessError::MissingPresent { letter, count } if *count > 1 => {
+                write!(f, "must contain {} {}", count, letter.to_uppercase())
+            }
+            GuessError::MissingPresent { letter, .. } => {
+                write!(f, "must contain {}", letter.to_uppercase())
+            }
+        }
+    }
+}
+impl std::error::Error for GuessError {}
+
+fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (1, 11) | (2, 12) | (3, 13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+/// Words of the small game the unit tests play.
+#[cfg(test)]
+pub(crate) const TEST_WORDS: [&str; 4] = ["cigar", "rebut", "sissy", "humph"];
+
+/// A game over [`TEST_WORDS`] with `answer`, for the unit tests.
+#[cfg(test)]
+pub(crate) fn test_game(answer: &str) -> Game {
+    let words = WordList::from_words(TEST_WORDS, 5).unwrap();
+    let mut game = Game::from_word_lists(words.clone(), words, 0).unwrap();
+    game.set_game_with_answer(answer.to_string()).unwrap();
+    game
+}
+
+/// Play `word` in a test game, which must accept it.
+#[cfg(test)]
+pub(crate) fn test_play(game: &mut Game, word: &str) {
+    let guess = Guess {
+        state: word.to_string(),
+    };
+    game.validate_guess(&guess).unwrap();
+    let one_match = game.grade_guess(&guess);
+    game.progress_game(&guess, Arc::new(one_match));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feedback::parse_tiles;
+
+    fn tiles(text: &str) -> Match {
+        parse_tiles(text).unwrap()
+    }
+
+    #[test]
+    fn repeated_letters_get_no_more_hints_than_the_answer_holds() {
+        assert_eq!(grade("eerie", "cheer"), tiles("yyybb"));
+        assert_eq!(grade("speed", "abide"), tiles("bbyby"));
+    }
+
+    #[test]
+    fn exact_matches_are_all_green() {
+        assert!(grade("cigar", "cigar").is_correct());
+        assert_eq!(grade("cigar", "cigar"), tiles("ggggg"));
+    }
+
+    #[test]
+    fn greens_are_assigned_before_yellows() {
+        // One L of the answer backs the green, leaving one for a yellow
+        assert_eq!(grade("lolly", "allay"), tiles("ybgbg"));
+    }
+
+    #[test]
+    fn undo_drops_hints_taken_after_the_guess() {
+        let mut game = test_game("cigar");
+        game.hint(HintKind::RemainingCount).unwrap();
+        test_play(&mut game, "rebut");
+        game.hint(HintKind::RevealLetter).unwrap();
+        let cost = game.hint_costs();
+        assert_eq!(game.hint_cost(), cost.remaining_count + cost.reveal_letter);
+        game.undo().unwrap();
+        assert_eq!(game.round(), 0);
+        assert_eq!(game.transcript().hints.len(), 1);
+        assert_eq!(game.hint_cost(), cost.remaining_count);
+    }
+
+    fn hard_game(answer: &str) -> Game {
+        let words = ["cheer", "sheer", "there", "rebut", "eerie", "trees"];
+        let words = WordList::from_words(words, 5).unwrap();
+        let mut game = Game::from_word_lists(words.clone(), words, 0).unwrap();
+        game.set_game_with_answer(answer.to_string()).unwrap();
+        game.set_hard_mode(true);
+        game
+    }
+
+    fn validate(game: &Game, word: &str) -> std::result::Result<(), GuessError> {
+        game.validate_guess(&Guess {
+            state: word.to_string(),
+        })
+    }
+
+    #[test]
+    fn hard_mode_keeps_greens_in_place() {
+        let mut game = hard_game("cheer");
+        test_play(&mut game, "sheer");
+        let err = validate(&game, "there").unwrap_err();
+        assert_eq!(
+            err,
+            GuessError::MissingCorrect {
+                position: 3,
+                letter: 'e'
+            }
+        );
+        assert_eq!(err.to_string(), "4th letter must be E");
+        assert_eq!(validate(&game, "cheer"), Ok(()));
+    }
+
+    #[test]
+    fn hard_mode_counts_repeated_yellows() {
+        let mut game = hard_game("cheer");
+        // Two yellow Es and a yellow R
+        test_play(&mut game, "eerie");
+        let err = validate(&game, "rebut").unwrap_err();
+        assert_eq!(
+            err,
+            GuessError::MissingPresent {
+                letter: 'e',
+                count: 2
+            }
+        );
+        assert_eq!(err.to_string(), "must contain 2 E");
+        assert_eq!(validate(&game, "trees"), Ok(()));
+    }
+
+    #[test]
+    fn hard_mode_is_off_by_default() {
+        let mut game = hard_game("cheer");
+        game.set_hard_mode(false);
+        test_play(&mut game, "sheer");
+        assert_eq!(validate(&game, "rebut"), Ok(()));
+    }
+
+    #[test]
+    fn guess_errors_read_as_messages() {
+        let single = GuessError::MissingPresent {
+            letter: 's',
+            count: 1,
+        };
+        assert_eq!(single.to_string(), "must contain S");
+        let second = GuessError::MissingCorrect {
+            position: 1,
+            letter: 'r',
+        };
+        assert_eq!(second.to_string(), "2nd letter must be R");
+        assert_eq!(GuessError::GameFinished.to_string(), "Game is over");
+        assert_eq!(GuessError::NotInWordList.to_string(), "Not in word list");
+        assert_eq!(
+            GuessError::NotAnEquation.to_string(),
+            "Not a valid equation"
+        );
+        assert_eq!(ordinal(11), "11th");
+        assert_eq!(ordinal(22), "22nd");
+        assert_eq!(ordinal(13), "13th");
+    }
+
+    #[test]
+    fn finished_games_reject_guesses() {
+        let mut game = hard_game("cheer");
+        test_play(&mut game, "cheer");
+        assert_eq!(validate(&game, "trees"), Err(GuessError::GameFinished));
+        assert_eq!(
+            validate(&hard_game("cheer"), "words"),
+            Err(GuessError::NotInWordList)
+        );
+    }
+
+    #[test]
+    fn games_end_after_their_rounds() {
+        let mut game = test_game("cigar");
+        assert!(matches!(
+            game.set_max_rounds(0),
+            Err(Error::InvalidRounds(0))
+        ));
+        game.set_max_rounds(2).unwrap();
+        test_play(&mut game, "rebut");
+        assert_eq!(game.state, GameState::On);
+        test_play(&mut game, "sissy");
+        assert_eq!(game.state, GameState::Over);
+    }
+
+    #[test]
+    fn only_tile_feedback_can_lie() {
+        let mut game = test_game("cigar");
+        assert!(matches!(game.set_lies(5), Err(Error::InvalidLies(_))));
+        game.set_lies(1).unwrap();
+        assert!(matches!(
+            game.set_feedback_kind(FeedbackKind::Jotto),
+            Err(Error::InvalidLies(_))
+        ));
+        assert_eq!(game.feedback_kind(), FeedbackKind::Tiles);
+
+        game.set_lies(0).unwrap();
+        game.set_feedback_kind(FeedbackKind::Mastermind).unwrap();
+        assert!(matches!(game.set_lies(1), Err(Error::InvalidLies(_))));
+    }
+
+    #[test]
+    fn seeds_pick_the_same_answers_everywhere() {
+        let answers = WordList::embedded_answers();
+        let candidates = WordList::embedded_candidates();
+        let mut game = Game::from_word_lists(answers, candidates, 42).unwrap();
+        assert_eq!(game.answer(), "churn");
+        game.reset();
+        assert_eq!(game.answer(), "essay");
+    }
+}
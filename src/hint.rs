@@ -0,0 +1,104 @@
+use std::fmt;
+
+/// The kinds of help a player can ask for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintKind {
+    /// Reveal the answer's letter at one position not yet solved.
+    RevealLetter,
+    /// How many possible answers are left.
+    RemainingCount,
+    /// The solver's best next guess.
+    BestGuess,
+}
+
+impl HintKind {
+    pub const ALL: [HintKind; 3] = [
+        HintKind::RemainingCount,
+        HintKind::RevealLetter,
+        HintKind::BestGuess,
+    ];
+    pub fn name(&self) -> &'static str {
+        match self {
+            HintKind::RevealLetter => "reveal_letter",
+            HintKind::RemainingCount => "remaining_count",
+            HintKind::BestGuess => "best_guess",
+        }
+    }
+    pub fn parse(name: &str) -> Option<HintKind> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+/// A hint handed to the player.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Hint {
+    RevealLetter { position: usize, letter: char },
+    RemainingCount(usize),
+    BestGuess(String),
+}
+
+impl Hint {
+    pub fn kind(&self) -> HintKind {
+        match self {
+            Hint::RevealLetter { .. } => HintKind::RevealLetter,
+            Hint::RemainingCount(_) => HintKind::RemainingCount,
+            Hint::BestGuess(_) => HintKind::BestGuess,
+        }
+    }
+}
+
+impl fmt::Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Hint::RevealLetter { position, letter } => {
+                write!(f, "Letter {} is {}", position + 1, letter.to_uppercase())
+            }
+            Hint::RemainingCount(1) => write!(f, "1 word left"),
+            Hint::RemainingCount(count) => write!(f, "{} words left", count),
+            Hint::BestGuess(word) => write!(f, "Try {}", word.to_uppercase()),
+        }
+    }
+}
+
+/// Penalty points charged for each kind of hint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HintCosts {
+    pub reveal_letter: u32,
+    pub remaining_count: u32,
+    pub best_guess: u32,
+}
+
+impl Default for HintCosts {
+    fn default() -> Self {
+        HintCosts {
+            reveal_letter: 2,
+            remaining_count: 1,
+            best_guess: 3,
+        }
+    }
+}
+
+impl HintCosts {
+    pub fn cost(&self, kind: HintKind) -> u32 {
+        match kind {
+            HintKind::RevealLetter => self.reveal_letter,
+            HintKind::RemainingCount => self.remaining_count,
+            HintKind::BestGuess => self.best_guess,
+        }
+    }
+    /// Parse `reveal,remaining,best`, e.g. `2,1,3` as given on the command line.
+    pub fn parse(text: &str) -> Option<HintCosts> {
+        let costs = text
+            .split(',')
+            .map(|cost| cost.trim().parse().ok())
+            .collect::<Option<Vec<u32>>>()?;
+        match costs[..] {
+            [reveal_letter, remaining_count, best_guess] => Some(HintCosts {
+                reveal_letter,
+                remaining_count,
+                best_guess,
+            }),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use super::alphabet::Alphabet;
+use super::feedback::FeedbackKind;
+use super::game::*;
+
+/// What the graded guesses so far say about one letter.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LetterInfo {
+    /// Best feedback the letter has had, `None` while it is unused.
+    pub state: Option<GuessState>,
+    /// Positions the letter is known to be at.
+    pub known_at: Vec<usize>,
+    /// Positions the letter is known not to be at.
+    pub not_at: Vec<usize>,
+}
+
+/// Per-letter knowledge merged from every graded guess, in alphabet order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keyboard {
+    alphabet: Alphabet,
+    letters: Vec<LetterInfo>,
+}
+
+impl Keyboard {
+    /// A keyboard with every letter still unused.
+    pub fn new(alphabet: &Alphabet) -> Keyboard {
+        Keyboard {
+            alphabet: alphabet.clone(),
+            letters: vec![LetterInfo::default(); alphabet.len()],
+        }
+    }
+    /// Knowledge from every guess in the game's transcript. Only tile
+    /// feedback tells about single letters; other kinds leave all unused.
+    pub fn from_game(game: &Game) -> Keyboard {
+        let mut keyboard = Self::new(game.alphabet());
+        if game.feedback_kind() != FeedbackKind::Tiles {
+            return keyboard;
+        }
+        for entry in game.transcript().entries.iter() {
+            keyboard.record(&entry.guess, &entry.feedback);
+        }
+        keyboard
+    }
+    /// Merge one graded guess. Correct beats Misplace, which beats Wrong.
+    /// Letters outside the alphabet are ignored.
+    pub fn record(&mut self, guess: &str, feedback: &Match) {
+        for (position, (c, state)) in guess.chars().zip(feedback.states.iter()).enumerate() {
+            let Some(index) = self.alphabet.index_of(c) else {
+                continue;
+            };
+            let info = &mut self.letters[index as usize];
+            info.state = info.state.max(Some(*state));
+            let positions = match state {
+                GuessState::Correct => &mut info.known_at,
+                // A wrong copy of a letter found elsewhere rules this spot out too
+                GuessState::Misplace | GuessState::Wrong => &mut info.not_at,
+            };
+            if !positions.contains(&position) {
+                positions.push(position);
+                positions.sort_unstable();
+            }
+        }
+    }
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+    pub fn letter(&self, c: char) -> Option<&LetterInfo> {
+        self.alphabet
+            .index_of(c)
+            .map(|index| &self.letters[index as usize])
+    }
+    /// Best feedback for `c`, `None` if it has not been guessed.
+    pub fn state(&self, c: char) -> Option<GuessState> {
+        self.letter(c).and_then(|info| info.state)
+    }
+    /// Letters not in any guess yet, in alphabet order.
+    pub fn unused(&self) -> Vec<char> {
+        self.iter()
+            .filter(|(_, info)| info.state.is_none())
+            .map(|(c, _)| c)
+            .collect()
+    }
+    /// Every letter with its knowledge, in alphabet order.
+    pub fn iter(&self) -> impl Iterator<Item = (char, &LetterInfo)> + '_ {
+        self.alphabet
+            .symbols()
+            .iter()
+            .copied()
+            .zip(self.letters.iter())
+    }
+}
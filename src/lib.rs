@@ -0,0 +1,20 @@
+pub mod absurdle;
+pub mod alphabet;
+pub mod challenge;
+pub mod daily;
+pub mod error;
+pub mod feedback;
+pub mod game;
+pub mod hint;
+pub mod keyboard;
+pub mod matrix;
+pub mod multi;
+pub mod nerdle;
+pub mod query;
+pub mod share;
+pub mod solver;
+pub mod stats;
+pub mod transcript;
+pub mod wordlist;
+
+pub use error::{Error, Result};
@@ -0,0 +1,257 @@
+use super::error::{Error, Result};
+use super::game::*;
+use super::wordlist::WordList;
+use memmap2::Mmap;
+use rayon::prelude::*;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// Directory where [`PatternMatrix::cached`] keeps matrices between runs,
+/// one file per word list, see [`PatternMatrix::cache_path`].
+pub const PATTERN_CACHE_DIR: &str = "./data";
+
+const MAGIC: &[u8; 4] = b"RDLM";
+const MATRIX_VERSION: u32 = 1;
+/// Magic, version, word count and word list checksum.
+const HEADER_SIZE: usize = 4 + 4 + 8 + 8;
+/// Longest words whose patterns fit in one byte, `3^5 = 243`.
+pub const MAX_MATRIX_WORD_LENGTH: usize = 5;
+
+/// Feedback pattern index of every guess against every answer of one word
+/// list, one byte per pair, so scoring and filtering become lookups.
+#[derive(Debug)]
+pub struct PatternMatrix {
+    words: usize,
+    checksum: u64,
+    data: Storage,
+}
+
+#[derive(Debug)]
+enum Storage {
+    Owned(Vec<u8>),
+    Mapped(Mmap),
+}
+
+impl PatternMatrix {
+    /// Grade every pair of `words`. Rows are graded in parallel.
+    pub fn compute(words: &WordList) -> Result<PatternMatrix> {
+        if words.word_length() > MAX_MATRIX_WORD_LENGTH {
+            return Err(Error::UnsupportedWordLength(words.word_length()));
+        }
+        let encoded = words.encoded();
+        let word_length = words.word_length();
+        let mut data = vec![0; encoded.len() * encoded.len()];
+        data.par_chunks_mut(encoded.len().max(1))
+            .zip(encoded.par_iter())
+            .for_each(|(row, guess)| {
+                let mut states = [GuessState::Wrong; MAX_MATRIX_WORD_LENGTH];
+                let states = &mut states[..word_length];
+                for (cell, answer) in row.iter_mut().zip(encoded.iter()) {
+                    grade_into(guess, answer, states);
+                    // Same digits as `Match::pattern_index`
+                    *cell = states
+                        .iter()
+                        .fold(0, |index, state| index * 3 + *state as u8);
+                }
+            });
+        Ok(PatternMatrix {
+            words: encoded.len(),
+            checksum: words.checksum(),
+            data: Storage::Owned(data),
+        })
+    }
+    /// Memory-map a matrix saved for `words`. Files made for another list,
+    /// or by another version, are rejected as malformed.
+    pub fn load<P: AsRef<Path>>(path: P, words: &WordList) -> Result<PatternMatrix> {
+        let path = path.as_ref();
+        let malformed = |reason: &str| Error::MalformedData {
+            path: Some(path.to_path_buf()),
+            reason: reason.to_string(),
+        };
+        let file = File::open(path).map_err(|source| Error::MissingDataFile {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        // SAFETY: the cache is only ever replaced by renaming a complete file
+        // over it, never written in place, so the mapping stays unchanged.
+        let map = unsafe { Mmap::map(&file) }.map_err(|source| Error::MissingDataFile {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        if map.len() < HEADER_SIZE || &map[..4] != MAGIC {
+            return Err(malformed("not a pattern matrix"));
+        }
+        let field = |at: usize, size: usize| {
+            let mut bytes = [0; 8];
+            bytes[..size].copy_from_slice(&map[at..at + size]);
+            u64::from_le_bytes(bytes)
+        };
+        if field(4, 4) != MATRIX_VERSION as u64 {
+            return Err(malformed("unsupported pattern matrix version"));
+        }
+        let count = field(8, 8) as usize;
+        if count != words.len() || field(16, 8) != words.checksum() {
+            return Err(malformed("pattern matrix is for another word list"));
+        }
+        if map.len() != HEADER_SIZE + count * count {
+            return Err(malformed("truncated pattern matrix"));
+        }
+        Ok(PatternMatrix {
+            words: count,
+            checksum: words.checksum(),
+            data: Storage::Mapped(map),
+        })
+    }
+    /// Write the matrix to a temporary file and move it into place, so
+    /// readers never map a half-written file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let temporary = path.with_extension(format!("tmp.{}", std::process::id()));
+        let write_failed = |source| Error::WriteFailed {
+            path: path.to_path_buf(),
+            source,
+        };
+        let mut bytes = Vec::with_capacity(HEADER_SIZE + self.cells().len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&MATRIX_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(self.words as u64).to_le_bytes());
+        bytes.extend_from_slice(&self.checksum.to_le_bytes());
+        bytes.extend_from_slice(self.cells());
+        std::fs::write(&temporary, bytes).map_err(write_failed)?;
+        std::fs::rename(&temporary, path).map_err(write_failed)
+    }
+    /// The matrix for `words` saved at `path`, computed and saved first if
+    /// it is missing or stale. A cache that cannot be written is skipped
+    /// with a warning on stderr.
+    pub fn load_or_compute<P: AsRef<Path>>(path: P, words: &WordList) -> Result<PatternMatrix> {
+        let path = path.as_ref();
+        match Self::load(path, words) {
+            Ok(matrix) => Ok(matrix),
+            Err(Error::MissingDataFile { .. } | Error::MalformedData { .. }) => {
+                let matrix = Self::compute(words)?;
+                if let Err(err) = matrix.save(path) {
+                    eprintln!("warning: pattern matrix not cached: {}", err);
+                }
+                Ok(matrix)
+            }
+            Err(err) => Err(err),
+        }
+    }
+    /// [`PatternMatrix::load_or_compute`] at [`PatternMatrix::cache_path`].
+    pub fn cached(words: &WordList) -> Result<PatternMatrix> {
+        Self::load_or_compute(Self::cache_path(words), words)
+    }
+    /// Cache file for `words` in [`PATTERN_CACHE_DIR`]. The name holds the
+    /// list's checksum, so different lists do not overwrite each other.
+    pub fn cache_path(words: &WordList) -> PathBuf {
+        Path::new(PATTERN_CACHE_DIR).join(format!("patterns-{:016x}.bin", words.checksum()))
+    }
+    /// Number of words along each side.
+    pub fn len(&self) -> usize {
+        self.words
+    }
+    pub fn is_empty(&self) -> bool {
+        self.words == 0
+    }
+    /// Checksum of the word list the matrix was computed for.
+    pub fn checksum(&self) -> u64 {
+        self.checksum
+    }
+    /// Pattern indices of guess `guess` against every answer, in list order.
+    pub fn row(&self, guess: usize) -> &[u8] {
+        &self.cells()[guess * self.words..(guess + 1) * self.words]
+    }
+    pub fn get(&self, guess: usize, answer: usize) -> u8 {
+        self.row(guess)[answer]
+    }
+    fn cells(&self) -> &[u8] {
+        match &self.data {
+            Storage::Owned(data) => data,
+            Storage::Mapped(map) => &map[HEADER_SIZE..],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words() -> WordList {
+        WordList::from_words(["cigar", "rebut", "sissy", "humph", "eerie"], 5).unwrap()
+    }
+
+    /// A matrix for [`words`] saved under a per-test name.
+    fn saved(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("rordle-{}-{}.bin", name, std::process::id()));
+        PatternMatrix::compute(&words())
+            .unwrap()
+            .save(&path)
+            .unwrap();
+        path
+    }
+
+    fn assert_malformed(path: &Path, words: &WordList) {
+        assert!(matches!(
+            PatternMatrix::load(path, words),
+            Err(Error::MalformedData { .. })
+        ));
+    }
+
+    #[test]
+    fn cells_hold_the_grading_pattern() {
+        let words = words();
+        let matrix = PatternMatrix::compute(&words).unwrap();
+        for (g, guess) in words.iter().enumerate() {
+            for (a, answer) in words.iter().enumerate() {
+                let pattern = grade(guess, answer).pattern_index();
+                assert_eq!(matrix.get(g, a) as usize, pattern);
+            }
+        }
+    }
+
+    #[test]
+    fn saved_matrices_load_back() {
+        let path = saved("load");
+        let computed = PatternMatrix::compute(&words()).unwrap();
+        let loaded = PatternMatrix::load(&path, &words()).unwrap();
+        assert_eq!(loaded.cells(), computed.cells());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn each_word_list_has_its_own_cache() {
+        let other = WordList::from_words(["cigar", "rebut", "sissy", "humph", "awake"], 5).unwrap();
+        let path = PatternMatrix::cache_path(&words());
+        assert_ne!(path, PatternMatrix::cache_path(&other));
+        assert_eq!(path, PatternMatrix::cache_path(&words()));
+        assert!(path.starts_with(PATTERN_CACHE_DIR));
+    }
+
+    #[test]
+    fn a_wrong_checksum_is_rejected() {
+        let path = saved("checksum");
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[16] ^= 1;
+        std::fs::write(&path, bytes).unwrap();
+        assert_malformed(&path, &words());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn a_wrong_version_is_rejected() {
+        let path = saved("version");
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[4..8].copy_from_slice(&(MATRIX_VERSION + 1).to_le_bytes());
+        std::fs::write(&path, bytes).unwrap();
+        assert_malformed(&path, &words());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn another_word_list_is_rejected() {
+        let path = saved("other");
+        let other = WordList::from_words(["cigar", "rebut", "sissy", "humph", "awake"], 5).unwrap();
+        assert_malformed(&path, &other);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,192 @@
+use super::error::{Error, Result};
+use super::game::*;
+use std::sync::Arc;
+
+/// Several boards, each with its own answer, played against one shared
+/// stream of guesses: Dordle with 2 boards, Quordle with 4, Octordle with 8.
+#[derive(Debug, Clone)]
+pub struct MultiGame {
+    boards: Vec<Game>,
+    max_rounds: usize,
+    round: usize,
+    pub state: GameState,
+}
+
+impl MultiGame {
+    /// Rounds allowed for `boards` boards: 7 for 2, 9 for 4 and 13 for 8.
+    pub fn rounds_for(boards: usize) -> usize {
+        boards + 5
+    }
+    /// Start `boards` boards with distinct random answers drawn from the
+    /// seed chain of `template`, so the same template seed gives the same set.
+    /// There must be at least one board and no more than answers.
+    pub fn new(template: &Game, boards: usize) -> Result<MultiGame> {
+        if boards == 0 || boards > template.answers.len() {
+            return Err(Error::InvalidBoards(boards));
+        }
+        let mut board = template.clone();
+        board.reset_with_seed(template.seed());
+        let mut games: Vec<Game> = Vec::with_capacity(boards);
+        while games.len() < boards {
+            if games.iter().all(|game| game.answer() != board.answer()) {
+                games.push(board.clone());
+            }
+            board.reset();
+        }
+        Ok(Self::from_boards(games))
+    }
+    /// Start one board per answer in `answers`.
+    pub fn with_answers(template: &Game, answers: &[String]) -> Result<MultiGame> {
+        if answers.is_empty() {
+            return Err(Error::InvalidBoards(0));
+        }
+        let mut games = Vec::with_capacity(answers.len());
+        for answer in answers {
+            let mut board = template.clone();
+            board.set_game_with_answer(answer.clone())?;
+            games.push(board);
+        }
+        Ok(Self::from_boards(games))
+    }
+    fn from_boards(mut boards: Vec<Game>) -> MultiGame {
+        let max_rounds = Self::rounds_for(boards.len());
+        for board in boards.iter_mut() {
+            board
+                .set_max_rounds(max_rounds)
+                .expect("every board count allows some rounds");
+        }
+        MultiGame {
+            boards,
+            max_rounds,
+            round: 0,
+            state: GameState::On,
+        }
+    }
+    pub fn boards(&self) -> &[Game] {
+        &self.boards
+    }
+    pub fn round(&self) -> usize {
+        self.round
+    }
+    pub fn max_rounds(&self) -> usize {
+        self.max_rounds
+    }
+    pub fn solved(&self) -> usize {
+        self.boards
+            .iter()
+            .filter(|board| board.state == GameState::Correct)
+            .count()
+    }
+    pub fn validate_guess(&self, guess: &Guess) -> std::result::Result<(), GuessError> {
+        if self.state != GameState::On {
+            return Err(GuessError::GameFinished);
+        }
+        if !self.boards[0].candidates.contains(&guess.state) {
+            return Err(GuessError::NotInWordList);
+        }
+        Ok(())
+    }
+    /// Grade `guess` on every board still in play. The result holds one
+    /// entry per board, `None` for boards already solved.
+    pub fn submit(
+        &mut self,
+        guess: &Guess,
+    ) -> std::result::Result<Vec<Option<Arc<Match>>>, GuessError> {
+        self.validate_guess(guess)?;
+        let mut matches = Vec::with_capacity(self.boards.len());
+        for board in self.boards.iter_mut() {
+            if board.state != GameState::On {
+                matches.push(None);
+                continue;
+            }
+            let one_match = Arc::new(board.grade_guess(guess));
+            board.progress_game(guess, one_match.clone());
+            matches.push(Some(one_match));
+        }
+        self.round += 1;
+        self.state = if self.solved() == self.boards.len() {
+            GameState::Correct
+        } else if self.round >= self.max_rounds {
+            GameState::Over
+        } else {
+            GameState::On
+        };
+        Ok(matches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guess(word: &str) -> Guess {
+        Guess {
+            state: word.to_string(),
+        }
+    }
+
+    fn dordle() -> MultiGame {
+        let answers = ["cigar".to_string(), "sissy".to_string()];
+        MultiGame::with_answers(&test_game("cigar"), &answers).unwrap()
+    }
+
+    #[test]
+    fn rounds_grow_with_the_boards() {
+        assert_eq!(MultiGame::rounds_for(2), 7);
+        assert_eq!(MultiGame::rounds_for(4), 9);
+        assert_eq!(MultiGame::rounds_for(8), 13);
+        assert_eq!(dordle().max_rounds(), 7);
+        assert!(dordle()
+            .boards()
+            .iter()
+            .all(|board| board.max_rounds() == 7));
+    }
+
+    #[test]
+    fn solved_boards_stop_taking_guesses() {
+        let mut game = dordle();
+        let matches = game.submit(&guess("cigar")).unwrap();
+        assert!(matches[0].as_ref().unwrap().is_correct());
+        assert!(!matches[1].as_ref().unwrap().is_correct());
+        assert_eq!(game.solved(), 1);
+        assert_eq!(game.state, GameState::On);
+        let matches = game.submit(&guess("sissy")).unwrap();
+        assert!(matches[0].is_none());
+        assert_eq!(game.state, GameState::Correct);
+        assert_eq!(game.submit(&guess("rebut")), Err(GuessError::GameFinished));
+    }
+
+    #[test]
+    fn unsolved_boards_end_the_game_at_the_limit() {
+        let mut game = dordle();
+        for _ in 0..6 {
+            game.submit(&guess("rebut")).unwrap();
+            assert_eq!(game.state, GameState::On);
+        }
+        game.submit(&guess("cigar")).unwrap();
+        assert_eq!(game.state, GameState::Over);
+        assert_eq!(game.solved(), 1);
+    }
+
+    #[test]
+    fn board_counts_must_fit_the_answers() {
+        let template = test_game("cigar");
+        assert!(matches!(
+            MultiGame::new(&template, 0),
+            Err(Error::InvalidBoards(0))
+        ));
+        assert!(matches!(
+            MultiGame::new(&template, 5),
+            Err(Error::InvalidBoards(5))
+        ));
+        let game = MultiGame::new(&template, 4).unwrap();
+        let mut answers: Vec<String> = game.boards().iter().map(Game::answer).collect();
+        answers.sort();
+        answers.dedup();
+        assert_eq!(answers.len(), 4);
+        assert!(matches!(
+            MultiGame::with_answers(&template, &[]),
+            Err(Error::InvalidBoards(0))
+        ));
+    }
+}
@@ -0,0 +1,218 @@
+use super::alphabet::Alphabet;
+use super::wordlist::WordList;
+
+/// Symbols in a classic Nerdle equation.
+pub const NERDLE_LENGTH: usize = 8;
+
+const OPERATORS: [char; 4] = ['+', '-', '*', '/'];
+
+/// Every valid equation of `length` symbols, sorted. See
+/// [`is_valid_equation`] for the rules.
+pub fn equations(length: usize) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut lhs = String::new();
+    // The right-hand side takes at least one digit and the `=`
+    for lhs_length in 3..length.saturating_sub(1) {
+        build_lhs(&mut lhs, lhs_length, false, length, &mut found);
+    }
+    found.sort();
+    found
+}
+
+/// Equations fit to be answers: those without a lone `0` on either side,
+/// which would make the rest of the equation trivial.
+pub fn answers(length: usize) -> Vec<String> {
+    answers_among(&equations(length))
+}
+
+/// Answer and guess lists over [`Alphabet::nerdle`]: [`answers`] and every
+/// one of the [`equations`], generated once for both.
+pub fn word_lists(length: usize) -> (WordList, WordList) {
+    let list = |words: Vec<String>| {
+        WordList::from_words_in(Alphabet::nerdle(), words, length)
+            .expect("generated equations are valid words")
+    };
+    let equations = equations(length);
+    (list(answers_among(&equations)), list(equations))
+}
+
+fn answers_among(equations: &[String]) -> Vec<String> {
+    equations
+        .iter()
+        .filter(|equation| !has_lone_zero(equation))
+        .cloned()
+        .collect()
+}
+
+fn has_lone_zero(equation: &str) -> bool {
+    equation
+        .split(|c| OPERATORS.contains(&c) || c == '=')
+        .any(|number| number == "0")
+}
+
+/// Whether `text` is a true equation: numbers joined by `+-*/` on the left,
+/// one `=`, and a single non-negative number on the right. Numbers have no
+/// leading zeros and every division must come out whole.
+pub fn is_valid_equation(text: &str) -> bool {
+    let Some((lhs, rhs)) = text.split_once('=') else {
+        return false;
+    };
+    let Some(expected) = parse_number(rhs) else {
+        return false;
+    };
+    let Some(tokens) = tokenize(lhs) else {
+        return false;
+    };
+    tokens.len() > 1 && evaluate(&tokens) == Some(expected)
+}
+
+/// Value of an expression with the usual precedence, `None` if it is
+/// malformed or divides unevenly or by zero.
+pub fn evaluate_expression(text: &str) -> Option<i64> {
+    evaluate(&tokenize(text)?)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Number(i64),
+    Operator(char),
+}
+
+/// Append numbers and operators to `lhs` until it is `remaining` symbols
+/// longer, recording every completed equation of `length`.
+fn build_lhs(
+    lhs: &mut String,
+    remaining: usize,
+    has_operator: bool,
+    length: usize,
+    found: &mut Vec<String>,
+) {
+    // The first number still needs an operator and another number after it
+    let max_digits = if has_operator {
+        remaining
+    } else {
+        remaining - 2
+    };
+    for digits in 1..=max_digits {
+        let first = if digits == 1 {
+            0
+        } else {
+            10i64.pow(digits as u32 - 1)
+        };
+        for number in first..10i64.pow(digits as u32) {
+            let mark = lhs.len();
+            lhs.push_str(&number.to_string());
+            let left = remaining - digits;
+            if left == 0 {
+                if has_operator {
+                    if let Some(value) = evaluate_expression(lhs).filter(|value| *value >= 0) {
+                        let rhs = value.to_string();
+                        if lhs.len() + 1 + rhs.len() == length {
+                            found.push(format!("{}={}", lhs, rhs));
+                        }
+                    }
+                }
+            } else if left >= 2 {
+                for operator in OPERATORS {
+                    lhs.push(operator);
+                    build_lhs(lhs, left - 1, true, length, found);
+                    lhs.pop();
+                }
+            }
+            lhs.truncate(mark);
+        }
+    }
+}
+
+fn parse_number(text: &str) -> Option<i64> {
+    let leading_zero = text.len() > 1 && text.starts_with('0');
+    if text.is_empty() || leading_zero || !text.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+/// Numbers alternating with operators, starting and ending with a number.
+fn tokenize(text: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut number = String::new();
+    for c in text.chars() {
+        if OPERATORS.contains(&c) {
+            tokens.push(Token::Number(parse_number(&number)?));
+            tokens.push(Token::Operator(c));
+            number.clear();
+        } else {
+            number.push(c);
+        }
+    }
+    tokens.push(Token::Number(parse_number(&number)?));
+    Some(tokens)
+}
+
+fn evaluate(tokens: &[Token]) -> Option<i64> {
+    // Fold `*` and `/` into terms first, then add the terms up
+    let mut terms = Vec::new();
+    let mut sign = 1;
+    let mut term = None;
+    let mut pending = '+';
+    for token in tokens {
+        match *token {
+            Token::Number(n) => {
+                term = Some(match (term, pending) {
+                    (Some(t), '*') => t * n,
+                    (Some(t), '/') => (n != 0 && t % n == 0).then(|| t / n)?,
+                    _ => n,
+                });
+            }
+            Token::Operator(op @ ('*' | '/')) => pending = op,
+            Token::Operator(op) => {
+                terms.push(sign * term?);
+                sign = if op == '-' { -1 } else { 1 };
+                term = None;
+                pending = op;
+            }
+        }
+    }
+    terms.push(sign * term?);
+    Some(terms.iter().sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn true_equations_are_valid() {
+        assert!(is_valid_equation("48-32=16"));
+        assert!(is_valid_equation("3+4*5=23"));
+        assert!(is_valid_equation("12/4+1=4"));
+    }
+
+    #[test]
+    fn false_or_malformed_equations_are_invalid() {
+        assert!(!is_valid_equation("48-32=17"));
+        assert!(!is_valid_equation("3+4*5=35"));
+        assert!(!is_valid_equation("7/2+1=4"));
+        assert!(!is_valid_equation("05+3=8"));
+        assert!(!is_valid_equation("16=16"));
+        assert!(!is_valid_equation("1+2=3=3"));
+        assert!(!is_valid_equation("1+-2=-1"));
+        assert!(!is_valid_equation("1+2+3"));
+    }
+
+    #[test]
+    fn expressions_follow_precedence() {
+        assert_eq!(evaluate_expression("2+3*4"), Some(14));
+        assert_eq!(evaluate_expression("20-8/4*2"), Some(16));
+        assert_eq!(evaluate_expression("1/0"), None);
+    }
+
+    #[test]
+    fn answers_have_no_lone_zeros() {
+        let answers = answers(6);
+        assert!(answers.iter().all(|answer| is_valid_equation(answer)));
+        assert!(answers.contains(&"9+6=15".to_string()));
+        assert!(!answers.contains(&"0*12=0".to_string()));
+        assert!(equations(6).contains(&"0*12=0".to_string()));
+    }
+}
@@ -1,67 +1,216 @@
-pub mod game;
-pub mod solver;
-use game::*;
+use rordle::challenge::Challenge;
+use rordle::daily;
+use rordle::feedback::{Feedback, FeedbackKind};
+use rordle::game::*;
+use rordle::hint::{HintCosts, HintKind};
+use rordle::keyboard::Keyboard;
+use rordle::share::{share_text, EmojiSet};
+use rordle::stats::Stats;
+use rordle::wordlist::WordList;
+use std::cell::Cell;
+use std::path::PathBuf;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 slint::include_modules!();
 
 fn main() {
     use slint::Model;
 
-    let game = Rc::new(Mutex::new(Game::new()));
+    let args: Vec<String> = std::env::args().collect();
+    let word_length = match arg_value(&args, "--length") {
+        Some(value) => value.parse().unwrap_or_else(|_| {
+            eprintln!("Invalid --length {:?}", value);
+            std::process::exit(1);
+        }),
+        None => 5,
+    };
+    let custom_lists = arg_value(&args, "--answers").is_some()
+        || arg_value(&args, "--guesses").is_some()
+        || args.iter().any(|arg| arg == "--nerdle");
+    let lengths = available_word_lengths();
+    if !custom_lists && !lengths.contains(&word_length) {
+        let lengths: Vec<String> = lengths.iter().map(usize::to_string).collect();
+        eprintln!(
+            "No word lists for length {}; available lengths: {}",
+            word_length,
+            lengths.join(", ")
+        );
+        std::process::exit(1);
+    }
+    let max_rounds = match arg_value(&args, "--rounds") {
+        Some(value) => value.parse().unwrap_or_else(|_| {
+            eprintln!("Invalid --rounds {:?}", value);
+            std::process::exit(1);
+        }),
+        None => 6,
+    };
+    let transcript_path = arg_value(&args, "--transcript").map(String::from);
+    let stats_path = arg_value(&args, "--stats")
+        .map(PathBuf::from)
+        .or_else(Stats::default_path);
+    let mut stats = match &stats_path {
+        Some(path) => Stats::load_or_default(path).unwrap_or_else(|err| {
+            println!("Failed to load stats: {}", err);
+            Stats::default()
+        }),
+        None => Stats::default(),
+    };
+    let emoji = arg_value(&args, "--emoji")
+        .and_then(EmojiSet::parse)
+        .unwrap_or(EmojiSet::Standard);
+
+    let mut game = match new_game(&args, word_length) {
+        Ok(game) => game,
+        Err(err) => {
+            eprintln!("Failed to start game: {}", err);
+            std::process::exit(1);
+        }
+    };
+    game.set_hard_mode(args.iter().any(|arg| arg == "--hard"));
+    if let Err(err) = game.set_max_rounds(max_rounds) {
+        eprintln!("Invalid --rounds: {}", err);
+        std::process::exit(1);
+    }
+    if let Some(kind) = arg_value(&args, "--feedback").and_then(FeedbackKind::parse) {
+        if let Err(err) = game.set_feedback_kind(kind) {
+            eprintln!("Invalid --feedback: {}", err);
+            std::process::exit(1);
+        }
+    }
+    if args.iter().any(|arg| arg == "--fibble") {
+        if let Err(err) = game.set_lies(1) {
+            eprintln!("Failed to start Fibble: {}", err);
+            std::process::exit(1);
+        }
+    }
+    if let Some(costs) = arg_value(&args, "--hint-costs").and_then(HintCosts::parse) {
+        game.set_hint_costs(costs);
+    }
+    if let Some(answer) = arg_value(&args, "--make-challenge") {
+        let challenge = Challenge {
+            answer: answer.to_lowercase(),
+            max_rounds: game.max_rounds(),
+            hard_mode: game.hard_mode(),
+        };
+        match challenge.encode(&game) {
+            Ok(code) => println!("Challenge code: {}", code),
+            Err(err) => eprintln!("Failed to make challenge: {}", err),
+        }
+        return;
+    }
+    if let Some(code) = arg_value(&args, "--challenge") {
+        if let Err(err) = Challenge::decode(code, &game).and_then(|c| c.apply(&mut game)) {
+            eprintln!("Failed to start challenge: {}", err);
+            std::process::exit(1);
+        }
+        println!("Challenge {}", code);
+    }
+    let daily_date = match arg_value(&args, "--date") {
+        Some(value) => match daily::Date::parse(value) {
+            Some(date) => Some(date),
+            None => {
+                eprintln!("Invalid --date {:?}, expected YYYY-MM-DD", value);
+                std::process::exit(1);
+            }
+        },
+        None => args
+            .iter()
+            .any(|arg| arg == "--daily")
+            .then(daily::Date::today),
+    };
+    if let Some(date) = daily_date {
+        match game.set_daily(date) {
+            Some(number) => println!("Daily puzzle #{} ({})", number, date),
+            None => println!("No daily puzzle on {}", date),
+        }
+    } else if game.mode() == GameMode::Random {
+        println!("Seed: {}", game.seed());
+    }
+    // A challenge brings its own number of rounds, equations their own length
+    let max_rounds = game.max_rounds();
+    let word_length = game.word_length();
+    let alphabet = game.alphabet().clone();
+    // Daily puzzles count for everyone, so only practice games allow undo
+    let practice = !matches!(game.mode(), GameMode::Daily(_));
+    let game = Arc::new(Mutex::new(game));
 
     let main_window = MainWindow::new().unwrap();
     let main_window_weak = main_window.as_weak().clone();
-    let new_data = vec![empty_charblock(); 30];
+    main_window_weak
+        .unwrap()
+        .set_word_length(word_length as i32);
+    main_window_weak.unwrap().set_max_rounds(max_rounds as i32);
+    main_window_weak.unwrap().set_can_undo(practice);
+    main_window_weak
+        .unwrap()
+        .set_stats_text(stats.summary().into());
+    let new_data = vec![empty_charblock(); word_length * max_rounds];
     let new_data = Rc::new(slint::VecModel::from(new_data));
     main_window_weak
         .unwrap()
         .set_char_items(new_data.clone().into());
+    let key_data = Rc::new(slint::VecModel::from(key_items(
+        &game.lock().unwrap().keyboard(),
+    )));
+    main_window_weak
+        .unwrap()
+        .set_key_items(key_data.clone().into());
     main_window_weak.unwrap().set_level(0);
     main_window_weak.unwrap().set_index(0);
 
     // Callback functions on handle keyboard input
     let char_items_handler = new_data.clone();
+    let keyboard_game = game.clone();
+    let hint_game = game.clone();
+    let key_items_handler = key_data.clone();
     main_window.on_handle_keyboard(move |text| {
+        let game = &keyboard_game;
         if &text as &str == "\n" {
-            let mut level = main_window_weak.unwrap().get_level() as usize;
-            let success = main_window_weak.unwrap().get_success();
-            if success {
-                return;
-            }
-            let failed = main_window_weak.unwrap().get_failed();
-            if failed {
+            if game.lock().unwrap().state != GameState::On {
                 return;
             }
+            let level = game.lock().unwrap().round();
 
-            let curr_word = format!(
-                "{}{}{}{}{}",
-                char_items_handler.row_data(level * 5).unwrap().text,
-                char_items_handler.row_data(level * 5 + 1).unwrap().text,
-                char_items_handler.row_data(level * 5 + 2).unwrap().text,
-                char_items_handler.row_data(level * 5 + 3).unwrap().text,
-                char_items_handler.row_data(level * 5 + 4).unwrap().text,
-            );
+            let curr_word: String = (0..word_length)
+                .map(|i| {
+                    char_items_handler
+                        .row_data(level * word_length + i)
+                        .unwrap()
+                        .text
+                })
+                .collect::<Vec<_>>()
+                .concat()
+                .chars()
+                .map(|c| alphabet.normalize_char(c).unwrap_or(c))
+                .collect();
 
             println!("Trying to submit: {:?}", curr_word);
 
-            let guess = Guess {
-                state: curr_word.to_lowercase(),
-            };
-            if (game.lock().unwrap()).check_valid_guess(&guess) {
+            let guess = Guess { state: curr_word };
+            let validation = game.lock().unwrap().validate_guess(&guess);
+            if let Err(reason) = validation {
+                println!("Invalid Guess: {}", reason);
+                main_window_weak
+                    .unwrap()
+                    .set_invalid_message(reason.to_string().into());
+                main_window_weak.unwrap().set_invalid(true);
+            } else {
                 let res = game.lock().unwrap().grade_guess(&guess);
 
                 #[cfg(debug_assertions)]
                 println!("Match {:?}", res);
 
-                for i in 0..5 {
-                    let index = level * 5 + i;
+                let feedback = game.lock().unwrap().feedback(&res);
+                let shown = feedback.states(word_length);
+                for (i, state) in shown.iter().enumerate() {
+                    let index = level * word_length + i;
                     let mut new_state = char_items_handler.row_data(index).unwrap();
                     new_state.trial = false;
                     new_state.nonexist = false;
                     new_state.correct = false;
                     new_state.misplaced = false;
-                    match res.states[i] {
+                    match state {
                         GuessState::Wrong => {
                             new_state.nonexist = true;
                             char_items_handler.set_row_data(index, new_state);
@@ -76,47 +225,69 @@ fn main() {
                         }
                     }
                 }
-                game.lock().unwrap().progress_game(Arc::new(res));
-                if game.lock().unwrap().state == GameState::Correct {
-                    main_window_weak.unwrap().set_success(true);
-                    println!("Correct Guess!");
+                game.lock().unwrap().progress_game(&guess, Arc::new(res));
+                key_items_handler.set_vec(key_items(&game.lock().unwrap().keyboard()));
+                match game.lock().unwrap().state {
+                    GameState::Correct => {
+                        main_window_weak.unwrap().set_success(true);
+                        println!("Correct Guess!");
+                    }
+                    GameState::Over => {
+                        main_window_weak.unwrap().set_failed(true);
+                        println!("Game Over!");
+                    }
+                    _ => {}
                 }
-                level += 1;
-                if level == 6 {
-                    main_window_weak.unwrap().set_failed(true);
-                    println!("Game Over!");
+                if stats.record(&game.lock().unwrap()) {
+                    main_window_weak
+                        .unwrap()
+                        .set_stats_text(stats.summary().into());
+                    if let Some(path) = &stats_path {
+                        if let Err(err) = stats.save(path) {
+                            println!("Failed to save stats: {}", err);
+                        }
+                    }
                 }
+                if let Some(text) = share_text(&game.lock().unwrap(), emoji) {
+                    println!("{}", text);
+                }
+                if let Some(path) = &transcript_path {
+                    if let Err(err) = game.lock().unwrap().transcript().save(path) {
+                        println!("Failed to save transcript: {}", err);
+                    }
+                }
+                let level = game.lock().unwrap().round();
                 main_window_weak.unwrap().set_level(level as i32);
                 main_window_weak.unwrap().set_index(0);
-            } else {
-                println!("Invalid Guess");
-                main_window_weak.unwrap().set_invalid(true);
+                // Counts shown as pegs are easier to read spelled out
+                let message = match feedback {
+                    Feedback::Tiles(_) => String::new(),
+                    feedback => feedback.to_string(),
+                };
+                main_window_weak.unwrap().set_hint_message(message.into());
             }
         } else if &text as &str == "\u{8}" {
             let level = main_window_weak.unwrap().get_level();
             let mut index = main_window_weak.unwrap().get_index();
             if index > 0 {
-                char_items_handler
-                    .set_row_data((level * 5 + index - 1) as usize, empty_charblock());
+                char_items_handler.set_row_data(
+                    level as usize * word_length + index as usize - 1,
+                    empty_charblock(),
+                );
                 index -= 1;
             }
             main_window_weak.unwrap().set_index(index);
             main_window_weak.unwrap().set_invalid(false);
-        } else if text.chars().all(char::is_alphabetic) {
-            let success = main_window_weak.unwrap().get_success();
-            if success {
-                return;
-            }
-            let failed = main_window_weak.unwrap().get_failed();
-            if failed {
+        } else if let Some(symbol) = single_char(&text).and_then(|c| alphabet.normalize_char(c)) {
+            if game.lock().unwrap().state != GameState::On {
                 return;
             }
             let level = main_window_weak.unwrap().get_level();
             let mut index = main_window_weak.unwrap().get_index();
-            if index < 5 {
+            if (index as usize) < word_length {
                 char_items_handler.set_row_data(
-                    (level * 5 + index) as usize,
-                    build_charblock(&text.to_string().to_uppercase()),
+                    level as usize * word_length + index as usize,
+                    build_charblock(&display_symbol(symbol)),
                 );
                 index += 1;
                 main_window_weak.unwrap().set_invalid(false);
@@ -126,11 +297,84 @@ fn main() {
     });
     let main_window_weak = main_window.as_weak().clone();
 
+    // Callback function on taking back the last guess
+    let char_items_handler = new_data.clone();
+    let key_items_handler = key_data.clone();
+    main_window.on_undo(move || {
+        if !practice || game.lock().unwrap().state != GameState::On {
+            return;
+        }
+        let Some(entry) = game.lock().unwrap().undo() else {
+            return;
+        };
+        println!("Undo: {:?}", entry.guess);
+        key_items_handler.set_vec(key_items(&game.lock().unwrap().keyboard()));
+        let level = game.lock().unwrap().round();
+        // Clear whatever was typed on the row after the undone guess
+        if level + 1 < max_rounds {
+            for i in 0..word_length {
+                char_items_handler.set_row_data((level + 1) * word_length + i, empty_charblock());
+            }
+        }
+        // Put the undone guess back as typed letters so it can be edited
+        for (i, symbol) in entry.guess.chars().enumerate() {
+            char_items_handler.set_row_data(
+                level * word_length + i,
+                build_charblock(&display_symbol(symbol)),
+            );
+        }
+        main_window_weak.unwrap().set_level(level as i32);
+        main_window_weak.unwrap().set_index(word_length as i32);
+        main_window_weak.unwrap().set_invalid(false);
+    });
+    let main_window_weak = main_window.as_weak().clone();
+
+    // Callback function on asking for a hint, cheapest kind first. Hints are
+    // worked out on a copy of the game in the background, since the solver
+    // takes a while on large lists such as Nerdle's
+    let next_hint = Cell::new(0);
+    let hint_pending = Arc::new(AtomicBool::new(false));
+    main_window.on_hint(move || {
+        if hint_pending.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let kind = HintKind::ALL[next_hint.get() % HintKind::ALL.len()];
+        next_hint.set(next_hint.get() + 1);
+        let snapshot = hint_game.lock().unwrap().clone();
+        main_window_weak
+            .unwrap()
+            .set_hint_message("Working out a hint...".into());
+        let game = hint_game.clone();
+        let pending = hint_pending.clone();
+        let window = main_window_weak.clone();
+        std::thread::spawn(move || {
+            let hint = snapshot.find_hint(kind);
+            let message = {
+                let mut game = game.lock().unwrap();
+                match hint {
+                    // A guess played or undone meanwhile makes the hint stale
+                    Some(hint) if same_position(&game, &snapshot) => {
+                        game.record_hint(kind, hint.clone());
+                        println!("Hint: {} (hints cost {} so far)", hint, game.hint_cost());
+                        hint.to_string()
+                    }
+                    _ => String::new(),
+                }
+            };
+            pending.store(false, Ordering::SeqCst);
+            let _ = window.upgrade_in_event_loop(move |window| {
+                window.set_invalid(false);
+                window.set_hint_message(message.into());
+            });
+        });
+    });
+    let main_window_weak = main_window.as_weak().clone();
+
     // Callback function on reset games
     let char_items_handler = new_data.clone();
     main_window.on_reset(move || {
         println!("reset");
-        for i in 0..30 {
+        for i in 0..word_length * max_rounds {
             char_items_handler.set_row_data(i, empty_charblock())
         }
         main_window_weak.unwrap().set_level(0);
@@ -140,6 +384,71 @@ fn main() {
     main_window.run().unwrap();
 }
 
+/// Whether two games have the same answer and the same guesses played.
+fn same_position(game: &Game, other: &Game) -> bool {
+    let guesses = |game: &Game| {
+        game.transcript()
+            .entries
+            .iter()
+            .map(|entry| entry.guess.clone())
+            .collect::<Vec<_>>()
+    };
+    game.answer() == other.answer() && game.state == other.state && guesses(game) == guesses(other)
+}
+
+/// Value following `flag` on the command line.
+fn arg_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+/// Build an equation game for `--nerdle`, or a game from `--answers` and
+/// `--guesses` word lists when given, falling back to the lists under `data/`.
+fn new_game(args: &[String], word_length: usize) -> rordle::Result<Game> {
+    let seed = arg_value(args, "--seed")
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(rand::random);
+    if args.iter().any(|arg| arg == "--nerdle") {
+        return Game::nerdle(seed);
+    }
+    let answers = arg_value(args, "--answers");
+    let guesses = arg_value(args, "--guesses");
+    if answers.is_none() && guesses.is_none() {
+        return Game::with_seed(word_length, seed);
+    }
+    let answers = match answers {
+        Some(path) => WordList::load(path, word_length)?,
+        None => Game::with_seed(word_length, seed)?.answers,
+    };
+    let allowed = match guesses {
+        Some(path) => WordList::load(path, word_length)?,
+        None => answers.clone(),
+    };
+    Game::from_word_lists(answers, allowed, seed)
+}
+
+/// The only character of `text`, if it holds exactly one.
+fn single_char(text: &str) -> Option<char> {
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+/// Uppercase form of a symbol for its tile, unless uppercasing would turn
+/// it into several characters (as with `ß`).
+fn display_symbol(symbol: char) -> String {
+    let upper: String = symbol.to_uppercase().collect();
+    if upper.chars().count() == 1 {
+        upper
+    } else {
+        symbol.to_string()
+    }
+}
+
 fn build_charblock(text: &str) -> CharItem {
     //println!("New charblock: {:?}", text);
     CharItem {
@@ -153,3 +462,17 @@ fn build_charblock(text: &str) -> CharItem {
 fn empty_charblock() -> CharItem {
     build_charblock("")
 }
+/// One key per letter of the alphabet, coloured by the best feedback the
+/// letter has had. Unused letters keep the trial colour.
+fn key_items(keyboard: &Keyboard) -> Vec<CharItem> {
+    keyboard
+        .iter()
+        .map(|(symbol, info)| CharItem {
+            text: display_symbol(symbol).into(),
+            trial: info.state.is_none(),
+            correct: info.state == Some(GuessState::Correct),
+            misplaced: info.state == Some(GuessState::Misplace),
+            nonexist: info.state == Some(GuessState::Wrong),
+        })
+        .collect()
+}
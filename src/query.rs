@@ -0,0 +1,157 @@
+use super::alphabet::Alphabet;
+use super::error::{Error, Result};
+use super::solver::Solver;
+use super::wordlist::WordList;
+
+/// A dictionary search written as whitespace-separated terms:
+///
+/// - `s?a?e`: a template, a known symbol or `?` for each position;
+/// - `+rt`: letters the word must contain, repeated for several copies;
+/// - `-ino`: letters the word must not contain;
+/// - `!2e`: letters not at a position, counted from 1.
+///
+/// So `s?a?e +rt -ino !2e` finds words shaped like `s?a?e` with an R and a
+/// T, no I, N or O, and no E second.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    word_length: usize,
+    template: Vec<Option<char>>,
+    required: Vec<(char, usize)>,
+    excluded: Vec<char>,
+    not_at: Vec<(usize, char)>,
+}
+
+impl Query {
+    /// Parse a query for words of `word_length` spelled in `alphabet`.
+    pub fn parse(text: &str, alphabet: &Alphabet, word_length: usize) -> Result<Query> {
+        let invalid = |reason: String| Error::InvalidQuery(reason);
+        let symbol = |c: char| {
+            alphabet
+                .normalize_char(c)
+                .ok_or_else(|| invalid(format!("{:?} is not in the alphabet", c)))
+        };
+        let mut query = Query {
+            word_length,
+            template: vec![None; word_length],
+            required: Vec::new(),
+            excluded: Vec::new(),
+            not_at: Vec::new(),
+        };
+        let mut has_template = false;
+        for term in text.split_whitespace() {
+            if let Some(letters) = term.strip_prefix('+') {
+                for c in letters.chars() {
+                    let c = symbol(c)?;
+                    match query.required.iter_mut().find(|(r, _)| *r == c) {
+                        Some((_, count)) => *count += 1,
+                        None => query.required.push((c, 1)),
+                    }
+                }
+            } else if let Some(letters) = term.strip_prefix('-') {
+                for c in letters.chars() {
+                    query.excluded.push(symbol(c)?);
+                }
+            } else if let Some(rest) = term.strip_prefix('!') {
+                let digits = rest.chars().take_while(char::is_ascii_digit).count();
+                let position: usize = rest[..digits]
+                    .parse()
+                    .map_err(|_| invalid(format!("{:?} has no position", term)))?;
+                if !(1..=word_length).contains(&position) {
+                    return Err(invalid(format!("no position {} in {:?}", position, term)));
+                }
+                let letters = &rest[digits..];
+                if letters.is_empty() {
+                    return Err(invalid(format!("{:?} names no letters", term)));
+                }
+                for c in letters.chars() {
+                    query.not_at.push((position - 1, symbol(c)?));
+                }
+            } else {
+                if has_template {
+                    return Err(invalid(format!("second template {:?}", term)));
+                }
+                if term.chars().count() != word_length {
+                    return Err(Error::WrongLength {
+                        word: term.to_string(),
+                        expected: word_length,
+                    });
+                }
+                for (slot, c) in query.template.iter_mut().zip(term.chars()) {
+                    *slot = match c {
+                        '?' | '.' | '_' => None,
+                        c => Some(symbol(c)?),
+                    };
+                }
+                has_template = true;
+            }
+        }
+        Ok(query)
+    }
+    pub fn matches(&self, word: &str) -> bool {
+        let chars: Vec<char> = word.chars().collect();
+        if chars.len() != self.word_length {
+            return false;
+        }
+        let template_fits = self
+            .template
+            .iter()
+            .zip(chars.iter())
+            .all(|(slot, c)| slot.is_none_or(|s| s == *c));
+        template_fits
+            && self
+                .required
+                .iter()
+                .all(|(r, count)| chars.iter().filter(|c| *c == r).count() >= *count)
+            && !self.excluded.iter().any(|e| chars.contains(e))
+            && !self
+                .not_at
+                .iter()
+                .any(|(position, c)| chars[*position] == *c)
+    }
+    /// Words of `words` matching the query, best first by the solver's score
+    /// over the matches.
+    pub fn search(&self, words: &WordList) -> Vec<(String, f64)> {
+        let mut solver = Solver::new(words);
+        solver.retain(|word| self.matches(word));
+        solver.ranked()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(text: &str) -> Query {
+        Query::parse(text, &Alphabet::english(), 5).unwrap()
+    }
+
+    #[test]
+    fn every_kind_of_term_parses() {
+        let query = query("s?a?e +rt -ino !2e");
+        assert_eq!(
+            query.template,
+            vec![Some('s'), None, Some('a'), None, Some('e')]
+        );
+        assert_eq!(query.required, vec![('r', 1), ('t', 1)]);
+        assert_eq!(query.excluded, vec!['i', 'n', 'o']);
+        assert_eq!(query.not_at, vec![(1, 'e')]);
+        assert!(!query.matches("skate"));
+        assert!(query.matches("stare"));
+        assert!(!query.matches("snare"));
+    }
+
+    #[test]
+    fn repeated_required_letters_need_several_copies() {
+        let query = query("+ee");
+        assert!(query.matches("eerie"));
+        assert!(!query.matches("stare"));
+    }
+
+    #[test]
+    fn malformed_queries_are_rejected() {
+        let alphabet = Alphabet::english();
+        for text in ["s?a?e s???e", "!9e", "!e", "+r1", "s?a"] {
+            assert!(Query::parse(text, &alphabet, 5).is_err(), "{}", text);
+        }
+    }
+}
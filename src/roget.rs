@@ -1,57 +1,232 @@
-pub mod game;
-pub mod solver;
-use game::*;
-use solver::*;
+use rordle::feedback::{parse_tiles, Feedback};
+use rordle::game::*;
+use rordle::matrix::PatternMatrix;
+use rordle::query::Query;
+use rordle::share::{grid_row, EmojiSet};
+use rordle::solver::*;
+use rordle::wordlist::WordList;
+use std::io::{BufRead, Write};
 use std::sync::{Arc, Mutex};
 use std::thread;
 
+/// Games needing more guesses than this count as failures.
+const SCORED_ROUNDS: usize = 6;
+/// Round limit after which the solver gives up on a word.
+const GIVE_UP_ROUNDS: usize = 20;
+/// Best next guesses shown after each observation.
+const SHOWN_SUGGESTIONS: usize = 5;
+/// Remaining candidates are listed in full up to this many.
+const LISTED_CANDIDATES: usize = 30;
+/// Games solved by `--seed` without `--runs`.
+const RANDOM_RUNS: u64 = 10;
+
 fn main() {
-    //solve_one();
-    solve_all();
+    let args: Vec<String> = std::env::args().collect();
+    let number = |flag: &str| {
+        arg_value(&args, flag).map(|value| {
+            value.parse().unwrap_or_else(|_| {
+                eprintln!("Invalid {} {:?}", flag, value);
+                std::process::exit(1);
+            })
+        })
+    };
+    let seed = number("--seed");
+    let runs = number("--runs");
+    let result = if args.iter().any(|arg| arg == "--interactive" || arg == "-i") {
+        interactive()
+    } else if seed.is_some() || runs.is_some() {
+        let seed = seed.unwrap_or_else(rand::random);
+        println!("Seed: {}", seed);
+        solve_random(seed, runs.unwrap_or(RANDOM_RUNS) as usize)
+    } else {
+        solve_all()
+    };
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+}
+
+/// Value following `flag` on the command line.
+fn arg_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+/// Assist on a live puzzle: read each word played there with the colours it
+/// got, and print what is left and what to play next.
+fn interactive() -> rordle::Result<()> {
+    let words = WordList::embedded_candidates().union(&WordList::embedded_answers())?;
+    let mut solver = Solver::new(&words);
+    solver.set_matrix(Arc::new(PatternMatrix::cached(&words)?))?;
+    print_help();
+    println!("Open with {}", solver.suggest().0.state);
+    prompt();
+    for line in std::io::stdin().lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        let mut terms = line.split_whitespace();
+        match terms.next() {
+            None => {}
+            Some("quit" | "exit") => break,
+            Some("help") => print_help(),
+            Some("undo" | "back") => {
+                if solver.undo() {
+                    report(&solver);
+                } else {
+                    println!("Nothing to undo");
+                }
+            }
+            Some("restart" | "new") => {
+                solver.reset();
+                report(&solver);
+            }
+            Some("list") => println!("{}", solver.remaining_words().join(" ")),
+            Some("find") => {
+                let text: Vec<&str> = terms.collect();
+                match Query::parse(&text.join(" "), words.alphabet(), words.word_length()) {
+                    Ok(query) => {
+                        for (word, score) in query.search(&words).iter().take(LISTED_CANDIDATES) {
+                            println!("  {} {:.3}", word, score);
+                        }
+                    }
+                    Err(err) => println!("{}", err),
+                }
+            }
+            Some(guess) => {
+                let guess = guess.to_lowercase();
+                let feedback: String = terms.collect();
+                let Some(one_match) = parse_tiles(&feedback) else {
+                    println!("Cannot read colours {:?}, try gybbb or 21000", feedback);
+                    prompt();
+                    continue;
+                };
+                if !words.contains(&guess) {
+                    println!("{} is not in the word list", guess);
+                }
+                let row = grid_row(&one_match, EmojiSet::Standard);
+                match solver.observe(&guess, Feedback::Tiles(one_match)) {
+                    Ok(()) => {
+                        println!("{} {}", guess, row);
+                        report(&solver);
+                    }
+                    Err(err) => println!("{}", err),
+                }
+            }
+        }
+        prompt();
+    }
+    Ok(())
+}
+
+fn print_help() {
+    println!("Enter each word played and its colours: crane gybbb, crane 21000 or emoji");
+    println!("  undo       take back the last word");
+    println!("  restart    start a new puzzle");
+    println!("  list       show every remaining word");
+    println!("  find QUERY search the word list, e.g. find s?a?e +rt -ino !2e");
+    println!("  quit");
+}
+
+fn prompt() {
+    print!("> ");
+    let _ = std::io::stdout().flush();
+}
+
+/// Remaining candidates and the best next guesses.
+fn report(solver: &Solver) {
+    if solver.patterns().is_empty() {
+        println!("Open with {}", solver.suggest().0.state);
+        return;
+    }
+    let remaining = solver.remaining_words();
+    match remaining.len() {
+        0 => println!("No word fits, check the colours or undo"),
+        1 => println!("The answer is {}", remaining[0]),
+        count => {
+            println!("{} words remain", count);
+            for (word, score) in solver.suggestions(SHOWN_SUGGESTIONS) {
+                println!("  {} {:.3}", word, score);
+            }
+            if count <= LISTED_CANDIDATES {
+                println!("{}", remaining.join(" "));
+            }
+        }
+    }
 }
 
 #[allow(dead_code)]
-fn solve_one() {
-    let mut game = Game::new();
-    let mut solver = Solver::bind(&game);
-    game.set_game_with_answer(String::from("zonal"));
+fn solve_one() -> rordle::Result<()> {
+    let mut game = Game::new()?;
+    game.set_max_rounds(GIVE_UP_ROUNDS)?;
+    let mut solver = Solver::bind(&game)?;
+    game.set_game_with_answer(String::from("zonal"))?;
     println!("answer is {:}", game.answer());
     solver.reset();
-    let mut count = 0;
 
-    loop {
-        count += 1;
+    while game.state == GameState::On {
         let (guess, score) = solver.new_guess(game.round() as u8);
 
-        println!("{} {:?} {}", count, guess, score);
-
-        let one_match = solver.try_guess(guess, &mut game);
+        println!("{} {:?} {}", game.round() + 1, guess, score);
 
-        if one_match.is_some() && one_match.unwrap().is_correct() {
+        if solver.try_guess(guess, &mut game).is_none() {
             break;
         }
-        if count > 20 {
-            break;
+    }
+    Ok(())
+}
+
+/// Solve `runs` random games drawn from `seed`; the same seed replays the
+/// same sequence of answers.
+fn solve_random(seed: u64, runs: usize) -> rordle::Result<()> {
+    let mut game = Game::with_seed(5, seed)?;
+    game.set_max_rounds(GIVE_UP_ROUNDS)?;
+    let mut solver = Solver::bind(&game)?;
+
+    for _ in 0..runs {
+        solver.reset();
+        while game.state == GameState::On {
+            let (guess, _score) = solver.new_guess(game.round() as u8);
+
+            if solver.try_guess(guess, &mut game).is_none() {
+                break;
+            }
         }
+        println!(
+            "seed {} answer {} {:?} in {}",
+            game.seed(),
+            game.answer(),
+            game.state,
+            game.transcript().entries.len()
+        );
+        game.reset();
     }
+    Ok(())
 }
 
 #[allow(dead_code)]
-fn solve_all() {
+fn solve_all() -> rordle::Result<()> {
     let sum = Arc::new(Mutex::new(0));
     let fail = Arc::new(Mutex::new(0));
     let unsolve = Arc::new(Mutex::new(0));
     let count = Arc::new(Mutex::new(0));
     let total_thread = 8;
+    // Load the matrix once, rather than once per thread
+    let matrix = Arc::new(PatternMatrix::cached(Solver::words_for(&Game::new()?))?);
     let mut handlers = Vec::new();
     for t in 0..total_thread {
         let sum = sum.clone();
         let fail = fail.clone();
         let unsolve = unsolve.clone();
         let g_count = count.clone();
+        let matrix = matrix.clone();
         let handler = thread::spawn(move || {
-            let mut game = Game::new();
-            let mut solver = Solver::bind(&game);
+            let mut game = Game::new()?;
+            game.set_max_rounds(GIVE_UP_ROUNDS)?;
+            let mut solver = Solver::bind_with_matrix(&game, matrix)?;
             let total_run = game.answers.len();
             //let total_run = 1;
             let offset = t;
@@ -59,39 +234,35 @@ fn solve_all() {
                 if i % total_thread == offset {
                     game.set_game_with_answer_index(i);
                     solver.reset();
-                    let mut count = 0;
 
-                    loop {
-                        count += 1;
+                    while game.state == GameState::On {
                         let (guess, _score) = solver.new_guess(game.round() as u8);
 
-                        let one_match = solver.try_guess(guess, &mut game);
-
-                        if one_match.is_some() && one_match.unwrap().is_correct() {
-                            *g_count.lock().unwrap() += count;
-                            if count > 6 {
-                                let mut fail_handler = fail.lock().unwrap();
-                                *fail_handler += 1;
-                            }
+                        if solver.try_guess(guess, &mut game).is_none() {
                             break;
                         }
-                        if count > 20 {
-                            {
-                                let mut unsolve_handler = unsolve.lock().unwrap();
-                                *unsolve_handler += 1;
-                            }
-                            break;
+                    }
+                    if game.state == GameState::Correct {
+                        let count = game.round() + 1;
+                        *g_count.lock().unwrap() += count;
+                        if count > SCORED_ROUNDS {
+                            let mut fail_handler = fail.lock().unwrap();
+                            *fail_handler += 1;
                         }
+                    } else {
+                        let mut unsolve_handler = unsolve.lock().unwrap();
+                        *unsolve_handler += 1;
                     }
                     let mut sum_handler = sum.lock().unwrap();
                     *sum_handler += 1;
                 }
             }
+            Ok(())
         });
         handlers.push(handler);
     }
     for h in handlers.into_iter() {
-        h.join().unwrap();
+        h.join().unwrap()?;
     }
     println!("Total attempts: {:}", *sum.lock().unwrap());
     println!("Total failures: {:}", *fail.lock().unwrap());
@@ -100,4 +271,5 @@ fn solve_all() {
         "Average Trial: {:}",
         *count.lock().unwrap() as f64 / *sum.lock().unwrap() as f64
     );
+    Ok(())
 }
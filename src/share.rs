@@ -0,0 +1,122 @@
+use super::game::*;
+
+/// Symbols used for each tile colour in a share grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmojiSet {
+    /// 🟩 🟨 ⬛
+    Standard,
+    /// 🟧 🟦 ⬛, the orange and blue pair that stays apart for colour-blind players.
+    ColorBlind,
+    /// ✅ 🔄 ⬜, told apart by shape as well as colour.
+    HighContrast,
+}
+
+impl EmojiSet {
+    pub fn symbol(&self, state: GuessState) -> &'static str {
+        match (self, state) {
+            (EmojiSet::Standard, GuessState::Correct) => "🟩",
+            (EmojiSet::Standard, GuessState::Misplace) => "🟨",
+            (EmojiSet::Standard, GuessState::Wrong) => "⬛",
+            (EmojiSet::ColorBlind, GuessState::Correct) => "🟧",
+            (EmojiSet::ColorBlind, GuessState::Misplace) => "🟦",
+            (EmojiSet::ColorBlind, GuessState::Wrong) => "⬛",
+            (EmojiSet::HighContrast, GuessState::Correct) => "✅",
+            (EmojiSet::HighContrast, GuessState::Misplace) => "🔄",
+            (EmojiSet::HighContrast, GuessState::Wrong) => "⬜",
+        }
+    }
+    /// Parse a set name as given on the command line.
+    pub fn parse(name: &str) -> Option<EmojiSet> {
+        match name {
+            "standard" => Some(EmojiSet::Standard),
+            "colorblind" | "colourblind" => Some(EmojiSet::ColorBlind),
+            "contrast" | "high-contrast" => Some(EmojiSet::HighContrast),
+            _ => None,
+        }
+    }
+}
+
+/// Share text for a finished game: a header with the puzzle number and
+/// score, then one emoji row per guess. Rows show the feedback as the
+/// player saw it. `None` while the game is running.
+///
+/// ```text
+/// Rordle 240 3/6*
+///
+/// ⬛🟨⬛⬛🟩
+/// 🟩⬛🟩⬛🟩
+/// 🟩🟩🟩🟩🟩
+/// ```
+pub fn share_text(game: &Game, emoji: EmojiSet) -> Option<String> {
+    let score = match game.state {
+        GameState::Correct => game.transcript().entries.len().to_string(),
+        GameState::Over => "X".to_string(),
+        _ => return None,
+    };
+    let mut text = String::from("Rordle");
+    if let GameMode::Daily(number) = game.mode() {
+        text.push(' ');
+        text.push_str(&group_thousands(number));
+    }
+    text.push_str(&format!(" {}/{}", score, game.max_rounds()));
+    if game.hard_mode() {
+        text.push('*');
+    }
+    text.push('\n');
+    for entry in game.transcript().entries.iter() {
+        let shown = Match {
+            states: game.feedback(&entry.feedback).states(game.word_length()),
+        };
+        text.push('\n');
+        text.push_str(&grid_row(&shown, emoji));
+    }
+    Some(text)
+}
+
+pub fn grid_row(one_match: &Match, emoji: EmojiSet) -> String {
+    one_match
+        .states
+        .iter()
+        .map(|state| emoji.symbol(*state))
+        .collect()
+}
+
+/// Read the emoji rows out of pasted share text. Header and other lines
+/// are skipped; any of the emoji sets is accepted, as is the light-theme ⬜.
+pub fn parse_grid(text: &str) -> Vec<Match> {
+    text.lines().filter_map(parse_row).collect()
+}
+
+fn parse_row(line: &str) -> Option<Match> {
+    let states = line
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '\u{fe0f}')
+        .map(emoji_state)
+        .collect::<Option<Vec<_>>>()?;
+    if states.is_empty() {
+        return None;
+    }
+    Some(Match { states })
+}
+
+/// Tile state of an emoji from any of the sets, or the light-theme ⬜.
+pub(crate) fn emoji_state(c: char) -> Option<GuessState> {
+    match c {
+        '🟩' | '🟧' | '✅' => Some(GuessState::Correct),
+        '🟨' | '🟦' | '🔄' => Some(GuessState::Misplace),
+        '⬛' | '⬜' => Some(GuessState::Wrong),
+        _ => None,
+    }
+}
+
+fn group_thousands(number: usize) -> String {
+    let digits = number.to_string();
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    grouped
+}
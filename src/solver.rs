@@ -1,73 +1,255 @@
+use super::alphabet::Alphabet;
+use super::error::{Error, Result};
+use super::feedback::{Feedback, FeedbackKind};
 use super::game::*;
-use rayon::prelude::*;
-use std::io::prelude::*;
+use super::matrix::PatternMatrix;
+use super::wordlist::WordList;
+use std::collections::HashMap;
 use std::sync::Arc;
 
-const PATTERN_SIZE: usize = 243;
+/// Best first guesses for the classic Wordle and Nerdle lists. Scoring
+/// every word against every other takes long, so these are precomputed.
+const OPENERS: [&str; 2] = ["tares", "48-32=16"];
+
+/// Above this many feedback slots per surviving word, scoring counts the
+/// groups sparsely rather than in a vector of every slot.
+const SPARSE_SLOTS_PER_WORD: usize = 4;
+
 #[derive(Debug)]
 pub struct Solver {
     patterns: Vec<Pattern>,
     valid_table: Vec<bool>,
     candidates: Vec<String>,
+    /// Table index of every candidate.
+    positions: HashMap<String, usize>,
+    encoded: Vec<Vec<u8>>,
+    alphabet: Alphabet,
+    word_length: usize,
     pub current_candidate: String,
     survive: usize,
+    /// `valid_table` and `survive` as they were before each pattern.
+    history: Vec<(Vec<bool>, usize)>,
+    /// False tiles expected in every row of feedback.
+    lies: usize,
+    feedback_kind: FeedbackKind,
+    /// Checksum of the word list, to match a [`PatternMatrix`] against.
+    checksum: u64,
+    matrix: Option<Arc<PatternMatrix>>,
 }
 
-fn grade_pair(word: &String, candidate: &String) -> usize {
-    let pattern_bytes = candidate.as_bytes();
-    let word_bytes = word.as_bytes();
-    let mut wordvec = 0u32;
-    let mut pattern_index = 0;
+fn grade_pair(word: &[u8], candidate: &[u8]) -> usize {
+    grade_symbols(candidate, word).pattern_index()
+}
+
+/// Split `words` by the feedback pattern `guess` would receive against each
+/// of them, returning the number of words in every one of the `3^len`
+/// pattern slots. Words are given as symbol indices.
+pub fn partition<'a, I>(guess: &[u8], words: I) -> Vec<usize>
+where
+    I: IntoIterator<Item = &'a Vec<u8>>,
+{
+    partition_by(guess, words, FeedbackKind::Tiles)
+}
+
+/// Like [`partition`], for feedback of any kind. There is one slot per
+/// possible feedback, see [`FeedbackKind::slots`].
+pub fn partition_by<'a, I>(guess: &[u8], words: I, kind: FeedbackKind) -> Vec<usize>
+where
+    I: IntoIterator<Item = &'a Vec<u8>>,
+{
+    let mut pattern_matched = vec![0; kind.slots(guess.len())];
+    spread(guess, words, kind, 0, |index, _| {
+        pattern_matched[index] += 1
+    });
+    pattern_matched
+}
+
+/// Like [`partition`], for feedback with `lies` false tiles per row. Each
+/// word counts as an even share of every pattern its true feedback could be
+/// turned into, so the slots hold expected numbers of words.
+pub fn partition_with_lies<'a, I>(guess: &[u8], words: I, lies: usize) -> Vec<f64>
+where
+    I: IntoIterator<Item = &'a Vec<u8>>,
+{
+    let mut pattern_matched = vec![0.0; 3usize.pow(guess.len() as u32)];
+    spread(guess, words, FeedbackKind::Tiles, lies, |index, share| {
+        pattern_matched[index] += share
+    });
+    pattern_matched
+}
 
-    for byte in word_bytes.iter() {
-        wordvec |= char_to_bitvec(*byte);
+/// Sizes of the non-empty groups of [`partition_by`] or
+/// [`partition_with_lies`], in slot order. Counts sorted slot indices
+/// instead of a vector of every slot, for words long enough that the slots
+/// far outnumber the words.
+fn sparse_partition<'a, I>(guess: &[u8], words: I, kind: FeedbackKind, lies: usize) -> Vec<f64>
+where
+    I: IntoIterator<Item = &'a Vec<u8>>,
+{
+    let mut shares = Vec::new();
+    spread(guess, words, kind, lies, |index, share| {
+        shares.push((index, share))
+    });
+    shares.sort_by_key(|(index, _)| *index);
+    let mut groups: Vec<f64> = Vec::new();
+    let mut last = None;
+    for (index, share) in shares {
+        match groups.last_mut() {
+            Some(size) if last == Some(index) => *size += share,
+            _ => groups.push(share),
+        }
+        last = Some(index);
     }
-    for i in 0..5 {
-        pattern_index *= 3;
-        if word_bytes[i] == pattern_bytes[i] {
-            pattern_index += 2;
-        } else if wordvec & char_to_bitvec(pattern_bytes[i]) != 0 {
-            pattern_index += 1;
+    groups
+}
+
+/// Hand every word's feedback slot for `guess` to `add`, with the share of
+/// the word landing there: all of it for honest feedback, an even share of
+/// every pattern the lies could show otherwise.
+fn spread<'a, I, F>(guess: &[u8], words: I, kind: FeedbackKind, lies: usize, mut add: F)
+where
+    I: IntoIterator<Item = &'a Vec<u8>>,
+    F: FnMut(usize, f64),
+{
+    let all_green = 3usize.pow(guess.len() as u32) - 1;
+    let mut variants = Vec::new();
+    for word in words {
+        let index = match kind {
+            FeedbackKind::Tiles => grade_pair(word, guess),
+            kind => kind
+                .feedback(&grade_symbols(guess, word))
+                .index(guess.len()),
+        };
+        // The answer itself is always graded honestly
+        if lies == 0 || index == all_green {
+            add(index, 1.0);
+            continue;
+        }
+        variants.clear();
+        lie_variants(index, guess.len(), lies, 0, &mut variants);
+        // A wrong guess is never shown all green
+        variants.retain(|index| *index != all_green);
+        for index in variants.iter() {
+            add(*index, 1.0 / variants.len() as f64);
         }
     }
-    pattern_index
 }
 
-fn char_to_bitvec(c: u8) -> u32 {
-    1u32 << (c - 97)
+/// Every pattern index reached by changing `lies` digits of `index`, at
+/// positions `from` or later, to one of the two other states.
+fn lie_variants(index: usize, len: usize, lies: usize, from: usize, out: &mut Vec<usize>) {
+    if lies == 0 {
+        out.push(index);
+        return;
+    }
+    for position in from..len {
+        let weight = 3usize.pow(position as u32);
+        let digit = index / weight % 3;
+        for other in (0..3).filter(|other| *other != digit) {
+            let changed = index - digit * weight + other * weight;
+            lie_variants(changed, len, lies - 1, position + 1, out);
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Pattern {
     pub chars: Arc<String>,
-    pub state: [GuessState; 5],
+    pub symbols: Vec<u8>,
+    pub feedback: Feedback,
+    /// Table index of the guess, if it is one of the solver's candidates.
+    pub index: Option<usize>,
 }
 
 impl Solver {
-    pub fn bind(game: &Game) -> Solver {
-        let table_size = game.candidates.len();
-        let mut cache_strings = String::new();
-        {
-            let mut cache_file = std::fs::File::open("./data/cache").unwrap();
-            cache_file.read_to_string(&mut cache_strings).unwrap();
+    /// Build a solver for `game`, backed by the cached pattern matrix of its
+    /// word list. The matrix is computed and cached on first use; words too
+    /// long for it are solved without one.
+    ///
+    /// Equation puzzles are solved over their answers only: scoring every
+    /// equation against every other is far too slow without a matrix.
+    pub fn bind(game: &Game) -> Result<Solver> {
+        let words = Self::words_for(game);
+        let mut solver = Self::unbound(game);
+        match PatternMatrix::cached(words) {
+            Ok(matrix) => solver.set_matrix(Arc::new(matrix))?,
+            Err(Error::UnsupportedWordLength(_)) => {}
+            Err(err) => return Err(err),
         }
-
+        Ok(solver)
+    }
+    /// Like [`Solver::bind`] with an already loaded matrix, so threads
+    /// solving the same lists can share one. The matrix must be built over
+    /// [`Solver::words_for`] the game.
+    pub fn bind_with_matrix(game: &Game, matrix: Arc<PatternMatrix>) -> Result<Solver> {
+        let mut solver = Self::unbound(game);
+        solver.set_matrix(matrix)?;
+        Ok(solver)
+    }
+    /// Words a solver bound to `game` guesses from: the answers for
+    /// equation puzzles, the candidates otherwise.
+    pub fn words_for(game: &Game) -> &WordList {
+        if game.is_nerdle() {
+            &game.answers
+        } else {
+            &game.candidates
+        }
+    }
+    fn unbound(game: &Game) -> Solver {
+        let mut solver = Self::new(Self::words_for(game));
+        solver.lies = game.lies();
+        solver.feedback_kind = game.feedback_kind();
+        solver
+    }
+    /// Build a solver treating every word of `words` as a possible answer.
+    pub fn new(words: &WordList) -> Solver {
         Solver {
             patterns: Vec::new(),
-            valid_table: vec![true; table_size],
-            candidates: game.candidates.clone(),
+            valid_table: vec![true; words.len()],
+            candidates: words.words().to_vec(),
+            positions: words
+                .iter()
+                .enumerate()
+                .map(|(index, word)| (word.clone(), index))
+                .collect(),
+            encoded: words.encoded().to_vec(),
+            alphabet: words.alphabet().clone(),
+            word_length: words.word_length(),
             current_candidate: String::new(),
-            survive: game.candidates.len(),
+            survive: words.len(),
+            history: Vec::new(),
+            lies: 0,
+            feedback_kind: FeedbackKind::Tiles,
+            checksum: words.checksum(),
+            matrix: None,
+        }
+    }
+    /// Score and filter by lookups in `matrix`, which must have been
+    /// computed for this solver's word list.
+    pub fn set_matrix(&mut self, matrix: Arc<PatternMatrix>) -> Result<()> {
+        if matrix.len() != self.candidates.len() || matrix.checksum() != self.checksum {
+            return Err(Error::MalformedData {
+                path: None,
+                reason: "pattern matrix is for another word list".to_string(),
+            });
         }
+        self.matrix = Some(matrix);
+        Ok(())
     }
     pub fn new_guess(&self, round: u8) -> (Guess, f64) {
+        // Opening guesses for the classic lists are precomputed
         if round == 0 {
-            return (
-                Guess {
-                    state: "tares".to_string(),
-                },
-                0.0,
-            );
+            if let Some(opener) = OPENERS
+                .iter()
+                .find(|opener| self.positions.contains_key(**opener))
+            {
+                return (
+                    Guess {
+                        state: opener.to_string(),
+                    },
+                    0.0,
+                );
+            }
         }
         if self.survive == 1 {
             for i in 0..self.candidates.len() {
@@ -88,8 +270,9 @@ impl Solver {
         #[cfg(debug_assertions)]
         let mut rank: Vec<(f64, &str)> = vec![];
 
+        let survivors = self.survivors();
         for i in 0..self.candidates.len() {
-            let new_score = self.calculate_score(i);
+            let new_score = self.calculate_score(i, &survivors);
 
             #[cfg(debug_assertions)]
             rank.push((-new_score, self.candidates[i].as_str()));
@@ -116,19 +299,86 @@ impl Solver {
             score,
         )
     }
+    /// Best next guess after the patterns observed so far, for puzzles
+    /// played without a [`Game`].
+    pub fn suggest(&self) -> (Guess, f64) {
+        self.new_guess(self.patterns.len().min(u8::MAX as usize) as u8)
+    }
+    /// The `count` best next guesses from the whole list, with their scores.
+    /// On equal scores, words that could still be the answer come first.
+    pub fn suggestions(&self, count: usize) -> Vec<(String, f64)> {
+        let survivors = self.survivors();
+        let mut scored: Vec<(usize, f64)> = (0..self.candidates.len())
+            .map(|i| (i, self.calculate_score(i, &survivors)))
+            .collect();
+        scored.sort_by(|a, b| {
+            b.1.total_cmp(&a.1)
+                .then_with(|| self.valid_word(b.0).cmp(&self.valid_word(a.0)))
+        });
+        scored
+            .into_iter()
+            .take(count)
+            .map(|(i, score)| (self.candidates[i].clone(), score))
+            .collect()
+    }
+    /// Narrow the candidates by a guess played elsewhere and the feedback it
+    /// got, e.g. on a live puzzle whose answer is unknown.
+    pub fn observe(&mut self, guess: &str, feedback: Feedback) -> Result<()> {
+        self.alphabet.check_word(guess, self.word_length)?;
+        if let Feedback::Tiles(one_match) = &feedback {
+            if one_match.states.len() != self.word_length {
+                return Err(Error::LengthMismatch {
+                    expected: self.word_length,
+                    found: one_match.states.len(),
+                });
+            }
+        }
+        self.push_pattern(guess.to_string(), feedback);
+        self.filter_valid_word();
+        Ok(())
+    }
+    /// Patterns observed so far, oldest first.
+    pub fn patterns(&self) -> &[Pattern] {
+        &self.patterns
+    }
+    /// Words still consistent with every pattern, in list order.
+    pub fn remaining_words(&self) -> Vec<&str> {
+        (0..self.candidates.len())
+            .filter(|i| self.valid_word(*i))
+            .map(|i| self.candidates[i].as_str())
+            .collect()
+    }
     pub fn try_guess(&mut self, guess: Guess, game: &mut Game) -> Option<Arc<Match>> {
         if !game.check_valid_guess(&guess) {
             return None;
         }
         let one_match = game.grade_guess(&guess);
         let shared_match = Arc::new(one_match);
-        game.progress_game(shared_match.clone());
+        game.progress_game(&guess, shared_match.clone());
         self.add_pattern(guess.state, shared_match.clone());
         #[cfg(debug_assertions)]
         println!("{:?}", shared_match);
         self.filter_valid_word();
         Some(shared_match)
     }
+    pub fn lies(&self) -> usize {
+        self.lies
+    }
+    /// Expect `lies` false tiles in every pattern added from now on.
+    pub fn set_lies(&mut self, lies: usize) {
+        self.lies = lies;
+    }
+    pub fn feedback_kind(&self) -> FeedbackKind {
+        self.feedback_kind
+    }
+    /// Read gradings passed to [`Solver::add_pattern`] as this kind of feedback.
+    pub fn set_feedback_kind(&mut self, feedback_kind: FeedbackKind) {
+        self.feedback_kind = feedback_kind;
+    }
+    /// Number of candidates still consistent with every pattern.
+    pub fn remaining(&self) -> usize {
+        self.survive
+    }
     fn valid_word(&self, table_index: usize) -> bool {
         self.valid_table[table_index]
     }
@@ -136,42 +386,116 @@ impl Solver {
         self.valid_table = vec![true; self.candidates.len()];
         self.patterns = Vec::new();
         self.current_candidate = String::new();
+        self.survive = self.candidates.len();
+        self.history = Vec::new();
+    }
+    /// Drop the most recent pattern and bring back the candidates it ruled
+    /// out. Returns `false` when there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some((valid_table, survive)) => {
+                self.patterns.pop();
+                self.valid_table = valid_table;
+                self.survive = survive;
+                true
+            }
+            None => false,
+        }
     }
 
+    /// Drop candidates failing `keep`. Not a pattern, so [`Solver::undo`]
+    /// does not step back over it.
+    pub fn retain<F: FnMut(&str) -> bool>(&mut self, mut keep: F) {
+        for (valid, word) in self.valid_table.iter_mut().zip(self.candidates.iter()) {
+            if *valid && !keep(word) {
+                *valid = false;
+                self.survive -= 1;
+            }
+        }
+    }
+    /// Surviving candidates, best next guess first, with their scores.
+    /// Ties keep list order.
+    pub fn ranked(&self) -> Vec<(String, f64)> {
+        let survivors = self.survivors();
+        let mut ranked: Vec<(String, f64)> = survivors
+            .iter()
+            .map(|i| {
+                (
+                    self.candidates[*i].clone(),
+                    self.calculate_score(*i, &survivors),
+                )
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked
+    }
+    /// Table indices of the candidates still valid.
+    fn survivors(&self) -> Vec<usize> {
+        (0..self.candidates.len())
+            .filter(|i| self.valid_word(*i))
+            .collect()
+    }
     fn filter_valid_word(&mut self) {
+        // Honest tile feedback on a listed guess is read off the matrix
+        let lookup = match (&self.matrix, self.lies, self.patterns.last()) {
+            (Some(matrix), 0, Some(pattern)) => match (&pattern.feedback, pattern.index) {
+                (Feedback::Tiles(seen), Some(guess)) => {
+                    Some((matrix.clone(), guess, seen.pattern_index() as u8))
+                }
+                _ => None,
+            },
+            _ => None,
+        };
         let mut survive = self.candidates.len();
         for table_index in 0..self.candidates.len() {
             if !self.valid_word(table_index) {
                 survive -= 1;
                 continue;
             }
-            let word = &self.candidates[table_index];
-            for i in self.patterns.iter() {
-                if !self.try_match(word, i) {
+            if let Some((matrix, guess, seen)) = &lookup {
+                if matrix.get(*guess, table_index) != *seen {
+                    self.valid_table[table_index] = false;
+                    survive -= 1;
+                }
+                continue;
+            }
+            let word = &self.encoded[table_index];
+            // Older patterns were applied when they were added
+            if let Some(pattern) = self.patterns.last() {
+                if !self.try_match(word, pattern) {
                     self.valid_table[table_index] = false;
                     survive -= 1;
-                    break;
                 }
             }
         }
         self.survive = survive;
     }
-    fn calculate_score(&self, table_index: usize) -> f64 {
-        let word = Arc::new(self.candidates[table_index].clone());
+    fn calculate_score(&self, table_index: usize, survivors: &[usize]) -> f64 {
+        let word = &self.encoded[table_index];
 
         let mut score: f64 = 0.0;
-        let mut total = 0;
-        let mut pattern_matched = vec![0; PATTERN_SIZE];
-        for j in 0..self.candidates.len() {
-            if self.valid_word(j) {
-                total += 1;
-                let pattern_index = grade_pair(&self.candidates[j], &word);
-                pattern_matched[pattern_index] += 1;
+        let words = survivors.iter().map(|j| &self.encoded[*j]);
+        let slots = self.feedback_kind.slots(self.word_length);
+        let as_f64 = |counts: Vec<usize>| counts.into_iter().map(|count| count as f64).collect();
+        let pattern_matched: Vec<f64> = match (&self.matrix, self.lies, self.feedback_kind) {
+            (Some(matrix), 0, FeedbackKind::Tiles) => {
+                let row = matrix.row(table_index);
+                let mut counts = vec![0; slots];
+                for j in survivors {
+                    counts[row[*j] as usize] += 1;
+                }
+                as_f64(counts)
             }
-        }
+            _ if slots > SPARSE_SLOTS_PER_WORD * survivors.len() => {
+                sparse_partition(word, words, self.feedback_kind, self.lies)
+            }
+            (_, 0, kind) => as_f64(partition_by(word, words, kind)),
+            _ => partition_with_lies(word, words, self.lies),
+        };
+        let total: f64 = pattern_matched.iter().sum();
         for i in pattern_matched.iter() {
-            if *i != 0 {
-                let p = *i as f64 / total as f64;
+            if *i > 0.0 {
+                let p = *i / total;
                 score -= p * p.log2();
             }
         }
@@ -188,41 +512,82 @@ impl Solver {
     ///
     ///
     ///
-    fn try_match(&self, word: &String, pattern: &Pattern) -> bool {
-        let pattern_bytes = pattern.chars.as_bytes();
-        let word_bytes = word.as_bytes();
-        let mut wordvec = 0u32;
-
-        for byte in word_bytes.iter() {
-            wordvec |= char_to_bitvec(*byte);
-        }
-        for i in 0..5 {
-            if word_bytes[i] == pattern_bytes[i] {
-                if pattern.state[i] != GuessState::Correct {
-                    return false;
-                }
-            } else if wordvec & char_to_bitvec(pattern_bytes[i]) != 0 {
-                if pattern.state[i] != GuessState::Misplace {
-                    return false;
-                }
-            } else if pattern.state[i] != GuessState::Wrong {
-                return false;
+    /// With lies expected, the word must disagree with the pattern on
+    /// exactly that many tiles, unless it is the guess itself.
+    fn try_match(&self, word: &[u8], pattern: &Pattern) -> bool {
+        let truth = grade_symbols(&pattern.symbols, word);
+        match &pattern.feedback {
+            Feedback::Tiles(seen) if self.lies > 0 && word != pattern.symbols.as_slice() => {
+                let disagreements = truth
+                    .states
+                    .iter()
+                    .zip(seen.states.iter())
+                    .filter(|(a, b)| a != b)
+                    .count();
+                disagreements == self.lies
             }
+            seen => seen.kind().feedback(&truth) == *seen,
         }
-        true
     }
 
     pub fn add_pattern(&mut self, word: String, one_match: Arc<Match>) {
+        let feedback = self.feedback_kind.feedback(&one_match);
+        self.push_pattern(word, feedback);
+    }
+    fn push_pattern(&mut self, word: String, feedback: Feedback) {
+        let symbols = self
+            .alphabet
+            .encode(&word)
+            .expect("guess is spelled in the solver's alphabet");
+        let index = self.positions.get(&word).copied();
         let boxed: Arc<String> = Arc::new(word);
-        self.patterns = vec![Pattern {
+        self.history.push((self.valid_table.clone(), self.survive));
+        self.patterns.push(Pattern {
             chars: boxed.clone(),
-            state: [
-                one_match.states[0],
-                one_match.states[1],
-                one_match.states[2],
-                one_match.states[3],
-                one_match.states[4],
-            ],
-        }];
+            symbols,
+            feedback,
+            index,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nonzero(counts: Vec<f64>) -> Vec<f64> {
+        counts.into_iter().filter(|count| *count > 0.0).collect()
+    }
+
+    #[test]
+    fn sparse_groups_match_the_dense_partition() {
+        let words = WordList::from_words(["cigar", "rebut", "sissy", "humph", "eerie"], 5).unwrap();
+        let guess = &words.encoded()[4];
+        let dense = partition(guess, words.encoded())
+            .into_iter()
+            .map(|count| count as f64);
+        let sparse = sparse_partition(guess, words.encoded(), FeedbackKind::Tiles, 0);
+        assert_eq!(sparse, nonzero(dense.collect()));
+        let dense = partition_with_lies(guess, words.encoded(), 1);
+        let sparse = sparse_partition(guess, words.encoded(), FeedbackKind::Tiles, 1);
+        assert_eq!(sparse, nonzero(dense));
+    }
+
+    #[test]
+    fn shared_matrices_cover_the_bound_words() {
+        let game = test_game("cigar");
+        let words = Solver::words_for(&game);
+        assert_eq!(words, &game.candidates);
+        let matrix = Arc::new(PatternMatrix::compute(words).unwrap());
+        let solver = Solver::bind_with_matrix(&game, matrix).unwrap();
+        assert_eq!(solver.remaining(), game.candidates.len());
+    }
+
+    #[test]
+    fn long_words_score_without_panicking() {
+        let words = ["abcdefghijk", "abcdefghijm", "bacdefghijk", "kjihgfedcba"];
+        let words = WordList::from_words(words, 11).unwrap();
+        let ranked = Solver::new(&words).ranked();
+        assert_eq!(ranked.len(), 4);
     }
 }
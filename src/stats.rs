@@ -0,0 +1,247 @@
+use super::error::{Error, Result};
+use super::game::*;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+const STATS_VERSION: u64 = 1;
+
+/// Lifetime results of one player, kept in a local versioned file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stats {
+    pub played: u32,
+    pub won: u32,
+    pub current_streak: u32,
+    pub max_streak: u32,
+    /// `guess_distribution[n - 1]` counts games won in `n` guesses.
+    pub guess_distribution: Vec<u32>,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Stats {
+            played: 0,
+            won: 0,
+            current_streak: 0,
+            max_streak: 0,
+            guess_distribution: vec![0; 6],
+        }
+    }
+}
+
+impl Stats {
+    /// Percentage of played games that were won.
+    pub fn win_rate(&self) -> f64 {
+        if self.played == 0 {
+            0.0
+        } else {
+            self.won as f64 * 100.0 / self.played as f64
+        }
+    }
+    /// Count a finished game. Games still in play are ignored and `false`
+    /// is returned.
+    pub fn record(&mut self, game: &Game) -> bool {
+        match game.state {
+            GameState::Correct => {
+                let guesses = game.transcript().entries.len().max(1);
+                if self.guess_distribution.len() < guesses {
+                    self.guess_distribution.resize(guesses, 0);
+                }
+                self.guess_distribution[guesses - 1] += 1;
+                self.won += 1;
+                self.current_streak += 1;
+                self.max_streak = self.max_streak.max(self.current_streak);
+            }
+            GameState::Over => self.current_streak = 0,
+            _ => return false,
+        }
+        self.played += 1;
+        true
+    }
+    /// One-line summary followed by the guess distribution.
+    pub fn summary(&self) -> String {
+        let distribution: Vec<String> = self
+            .guess_distribution
+            .iter()
+            .enumerate()
+            .map(|(i, count)| format!("{}:{}", i + 1, count))
+            .collect();
+        format!(
+            "Played {}  Win {:.0}%  Streak {}  Max {}\n{}",
+            self.played,
+            self.win_rate(),
+            self.current_streak,
+            self.max_streak,
+            distribution.join(" ")
+        )
+    }
+    /// `$XDG_DATA_HOME/rordle/stats.json`, falling back to
+    /// `~/.local/share` on Unix and `%APPDATA%` on Windows.
+    pub fn default_path() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+            .or_else(|| {
+                std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share"))
+            })?;
+        Some(base.join("rordle").join("stats.json"))
+    }
+    pub fn to_json(&self) -> String {
+        let value = json!({
+            "version": STATS_VERSION,
+            "played": self.played,
+            "won": self.won,
+            "current_streak": self.current_streak,
+            "max_streak": self.max_streak,
+            "guess_distribution": self.guess_distribution,
+        });
+        serde_json::to_string_pretty(&value).expect("stats are valid JSON")
+    }
+    pub fn from_json(text: &str) -> Result<Stats> {
+        Self::parse_from(text, None)
+    }
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Stats> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|source| Error::MissingDataFile {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse_from(&text, Some(path))
+    }
+    /// Like [`Stats::load`], but a file that does not exist yet gives empty stats.
+    pub fn load_or_default<P: AsRef<Path>>(path: P) -> Result<Stats> {
+        match Self::load(path) {
+            Err(Error::MissingDataFile { source, .. })
+                if source.kind() == std::io::ErrorKind::NotFound =>
+            {
+                Ok(Stats::default())
+            }
+            result => result,
+        }
+    }
+    /// Write the stats, creating the parent directory when needed.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let write_failed = |source| Error::WriteFailed {
+            path: path.to_path_buf(),
+            source,
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(write_failed)?;
+        }
+        std::fs::write(path, self.to_json()).map_err(write_failed)
+    }
+    fn parse_from(text: &str, path: Option<&Path>) -> Result<Stats> {
+        let malformed = |reason: &str| Error::MalformedData {
+            path: path.map(Path::to_path_buf),
+            reason: reason.to_string(),
+        };
+        let value: Value = serde_json::from_str(text).map_err(|source| Error::MalformedJson {
+            path: path.map(Path::to_path_buf),
+            source,
+        })?;
+        let version = value["version"]
+            .as_u64()
+            .ok_or_else(|| malformed("missing version"))?;
+        if version > STATS_VERSION {
+            return Err(malformed("unsupported stats version"));
+        }
+        let count = |key: &str| {
+            value[key]
+                .as_u64()
+                .map(|n| n as u32)
+                .ok_or_else(|| malformed(&format!("missing {}", key)))
+        };
+        let guess_distribution = value["guess_distribution"]
+            .as_array()
+            .ok_or_else(|| malformed("missing guess_distribution"))?
+            .iter()
+            .map(|n| n.as_u64().map(|n| n as u32))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| malformed("invalid guess_distribution"))?;
+        Ok(Stats {
+            played: count("played")?,
+            won: count("won")?,
+            current_streak: count("current_streak")?,
+            max_streak: count("max_streak")?,
+            guess_distribution,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finished_game(answer: &str, guesses: &[&str], rounds: usize) -> Game {
+        let mut game = test_game(answer);
+        game.set_max_rounds(rounds).unwrap();
+        for word in guesses {
+            test_play(&mut game, word);
+        }
+        game
+    }
+
+    #[test]
+    fn losses_reset_the_streak() {
+        let mut stats = Stats::default();
+        assert!(stats.record(&finished_game("cigar", &["cigar"], 6)));
+        assert!(stats.record(&finished_game("cigar", &["rebut", "cigar"], 6)));
+        assert_eq!((stats.current_streak, stats.max_streak), (2, 2));
+
+        let lost = finished_game("cigar", &["rebut"; 6], 6);
+        assert_eq!(lost.state, GameState::Over);
+        assert!(stats.record(&lost));
+        assert_eq!(stats.played, 3);
+        assert_eq!(stats.won, 2);
+        assert_eq!((stats.current_streak, stats.max_streak), (0, 2));
+        assert_eq!(stats.guess_distribution, [1, 1, 0, 0, 0, 0]);
+
+        assert!(!stats.record(&test_game("cigar")));
+        assert_eq!(stats.played, 3);
+    }
+
+    #[test]
+    fn long_wins_grow_the_distribution() {
+        let mut stats = Stats::default();
+        let guesses = [
+            "rebut", "sissy", "humph", "rebut", "sissy", "humph", "rebut", "cigar",
+        ];
+        assert!(stats.record(&finished_game("cigar", &guesses, 8)));
+        assert_eq!(stats.guess_distribution, [0, 0, 0, 0, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn stats_survive_a_round_trip() {
+        let mut stats = Stats::default();
+        stats.record(&finished_game("cigar", &["sissy", "cigar"], 6));
+        stats.record(&finished_game("cigar", &["humph"; 6], 6));
+        assert_eq!(Stats::from_json(&stats.to_json()).unwrap(), stats);
+
+        let path = std::env::temp_dir()
+            .join(format!("rordle-stats-{}", std::process::id()))
+            .join("stats.json");
+        assert_eq!(Stats::load_or_default(&path).unwrap(), Stats::default());
+        stats.save(&path).unwrap();
+        assert_eq!(Stats::load(&path).unwrap(), stats);
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn newer_versions_are_refused() {
+        let newer = Stats::default()
+            .to_json()
+            .replace("\"version\": 1", "\"version\": 2");
+        assert!(matches!(
+            Stats::from_json(&newer),
+            Err(Error::MalformedData { .. })
+        ));
+        assert!(matches!(
+            Stats::from_json("{\"played\": 1}"),
+            Err(Error::MalformedData { .. })
+        ));
+        assert!(matches!(
+            Stats::from_json("not json"),
+            Err(Error::MalformedJson { .. })
+        ));
+    }
+}
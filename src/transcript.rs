@@ -0,0 +1,387 @@
+use super::error::{Error, Result};
+use super::feedback::FeedbackKind;
+use super::game::*;
+use super::hint::{Hint, HintKind};
+use serde_json::{json, Map, Value};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const TRANSCRIPT_VERSION: u64 = 1;
+
+/// One submitted guess and the feedback it received.
+#[derive(Debug, Clone)]
+pub struct TranscriptEntry {
+    pub guess: String,
+    pub feedback: Arc<Match>,
+    /// Unix time in seconds the guess was graded.
+    pub at: u64,
+}
+
+/// A hint taken during the game.
+#[derive(Debug, Clone)]
+pub struct HintEntry {
+    pub hint: Hint,
+    /// Penalty points charged for it.
+    pub cost: u32,
+    /// Number of guesses graded before it was taken.
+    pub round: usize,
+    /// Unix time in seconds the hint was given.
+    pub at: u64,
+}
+
+/// Everything needed to archive a game and replay it later.
+#[derive(Debug, Clone)]
+pub struct Transcript {
+    pub answer: String,
+    pub mode: GameMode,
+    pub seed: u64,
+    pub word_length: usize,
+    pub hard_mode: bool,
+    /// Tiles per row given false feedback.
+    pub lies: usize,
+    pub feedback_kind: FeedbackKind,
+    pub max_rounds: usize,
+    /// Unix time in seconds the game started.
+    pub started_at: u64,
+    pub entries: Vec<TranscriptEntry>,
+    pub hints: Vec<HintEntry>,
+}
+
+impl Transcript {
+    /// Empty transcript for the game currently set up in `game`.
+    pub fn new(game: &Game) -> Transcript {
+        Transcript {
+            answer: game.answer(),
+            mode: game.mode(),
+            seed: game.seed(),
+            word_length: game.word_length(),
+            hard_mode: game.hard_mode(),
+            lies: game.lies(),
+            feedback_kind: game.feedback_kind(),
+            max_rounds: game.max_rounds(),
+            started_at: unix_time(),
+            entries: Vec::new(),
+            hints: Vec::new(),
+        }
+    }
+    pub fn record(&mut self, guess: &Guess, feedback: Arc<Match>) {
+        self.entries.push(TranscriptEntry {
+            guess: guess.state.clone(),
+            feedback,
+            at: unix_time(),
+        });
+    }
+    pub fn record_hint(&mut self, hint: Hint, cost: u32, round: usize) {
+        self.hints.push(HintEntry {
+            hint,
+            cost,
+            round,
+            at: unix_time(),
+        });
+    }
+    pub fn to_json(&self) -> String {
+        let mut value = json!({
+            "version": TRANSCRIPT_VERSION,
+            "answer": self.answer,
+            "seed": self.seed,
+            "word_length": self.word_length,
+            "hard_mode": self.hard_mode,
+            "lies": self.lies,
+            "feedback_kind": self.feedback_kind.name(),
+            "max_rounds": self.max_rounds,
+            "started_at": self.started_at,
+            "guesses": self.entries.iter().map(|entry| json!({
+                "guess": entry.guess,
+                "feedback": feedback_to_string(&entry.feedback),
+                "at": entry.at,
+            })).collect::<Vec<_>>(),
+            "hints": self.hints.iter().map(hint_to_json).collect::<Vec<_>>(),
+        });
+        let (mode, puzzle_number) = match self.mode {
+            GameMode::Random => ("random", None),
+            GameMode::Daily(number) => ("daily", Some(number)),
+            GameMode::Custom => ("custom", None),
+        };
+        value["mode"] = json!(mode);
+        if let Some(number) = puzzle_number {
+            value["puzzle_number"] = json!(number);
+        }
+        serde_json::to_string_pretty(&value).expect("transcript is valid JSON")
+    }
+    pub fn from_json(text: &str) -> Result<Transcript> {
+        Self::parse_from(text, None)
+    }
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_json()).map_err(|source| Error::WriteFailed {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Transcript> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|source| Error::MissingDataFile {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse_from(&text, Some(path))
+    }
+    /// Replay the recorded guesses one at a time on a copy of `template`,
+    /// which provides the word lists.
+    pub fn replay(&self, template: &Game) -> Result<Replay<'_>> {
+        let mut game = template.clone();
+        game.start_transcript(self)?;
+        Ok(Replay {
+            transcript: self,
+            game,
+            next: 0,
+        })
+    }
+    fn parse_from(text: &str, path: Option<&Path>) -> Result<Transcript> {
+        let malformed = |reason: &str| Error::MalformedData {
+            path: path.map(Path::to_path_buf),
+            reason: reason.to_string(),
+        };
+        let value: Value = serde_json::from_str(text).map_err(|source| Error::MalformedJson {
+            path: path.map(Path::to_path_buf),
+            source,
+        })?;
+        let object = value
+            .as_object()
+            .ok_or_else(|| malformed("transcript is not an object"))?;
+        let version = get_u64(object, "version").ok_or_else(|| malformed("missing version"))?;
+        if version > TRANSCRIPT_VERSION {
+            return Err(malformed("unsupported transcript version"));
+        }
+        let mode = match get_str(object, "mode") {
+            Some("random") => GameMode::Random,
+            Some("custom") => GameMode::Custom,
+            Some("daily") => GameMode::Daily(
+                get_u64(object, "puzzle_number")
+                    .ok_or_else(|| malformed("missing puzzle_number"))? as usize,
+            ),
+            _ => return Err(malformed("unknown mode")),
+        };
+        let feedback_kind = match get_str(object, "feedback_kind") {
+            Some(name) => {
+                FeedbackKind::parse(name).ok_or_else(|| malformed("unknown feedback_kind"))?
+            }
+            None => FeedbackKind::Tiles,
+        };
+        let mut entries = Vec::new();
+        for entry in object
+            .get("guesses")
+            .and_then(Value::as_array)
+            .ok_or_else(|| malformed("missing guesses"))?
+        {
+            let entry = entry
+                .as_object()
+                .ok_or_else(|| malformed("guess is not an object"))?;
+            let feedback = get_str(entry, "feedback")
+                .and_then(feedback_from_str)
+                .ok_or_else(|| malformed("invalid feedback"))?;
+            entries.push(TranscriptEntry {
+                guess: get_str(entry, "guess")
+                    .ok_or_else(|| malformed("missing guess"))?
+                    .to_string(),
+                feedback: Arc::new(feedback),
+                at: get_u64(entry, "at").unwrap_or(0),
+            });
+        }
+        // Transcripts written before hints existed have none
+        let mut hints = Vec::new();
+        for entry in object
+            .get("hints")
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+        {
+            let entry = entry
+                .as_object()
+                .ok_or_else(|| malformed("hint is not an object"))?;
+            hints.push(hint_from_json(entry).ok_or_else(|| malformed("invalid hint"))?);
+        }
+        let word_length = get_u64(object, "word_length")
+            .ok_or_else(|| malformed("missing word_length"))? as usize;
+        let lies = get_u64(object, "lies").unwrap_or(0) as usize;
+        if lies > word_length {
+            return Err(malformed("more lies than letters"));
+        }
+        Ok(Transcript {
+            answer: get_str(object, "answer")
+                .ok_or_else(|| malformed("missing answer"))?
+                .to_string(),
+            mode,
+            seed: get_u64(object, "seed").unwrap_or(0),
+            word_length,
+            hard_mode: object
+                .get("hard_mode")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            lies,
+            feedback_kind,
+            max_rounds: get_u64(object, "max_rounds").unwrap_or(6) as usize,
+            started_at: get_u64(object, "started_at").unwrap_or(0),
+            entries,
+            hints,
+        })
+    }
+}
+
+/// Step-by-step replay of a [`Transcript`].
+#[derive(Debug)]
+pub struct Replay<'a> {
+    transcript: &'a Transcript,
+    game: Game,
+    next: usize,
+}
+
+impl<'a> Replay<'a> {
+    /// The game as of the last replayed guess.
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+    /// Regrade the next recorded guess. Yields the recorded entry alongside
+    /// the fresh feedback so callers can spot diverging results; stops early
+    /// if the guess is no longer accepted.
+    pub fn step(&mut self) -> Option<(&'a TranscriptEntry, Arc<Match>)> {
+        let entry = self.transcript.entries.get(self.next)?;
+        let guess = Guess {
+            state: entry.guess.clone(),
+        };
+        self.game.validate_guess(&guess).ok()?;
+        let one_match = Arc::new(self.game.grade_guess(&guess));
+        self.game.progress_game(&guess, one_match.clone());
+        self.next += 1;
+        Some((entry, one_match))
+    }
+}
+
+impl<'a> Iterator for Replay<'a> {
+    type Item = (&'a TranscriptEntry, Arc<Match>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.step()
+    }
+}
+
+/// Current Unix time in seconds.
+pub(crate) fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Encode feedback as `g` (correct), `y` (misplace) and `b` (wrong).
+fn feedback_to_string(one_match: &Match) -> String {
+    one_match
+        .states
+        .iter()
+        .map(|state| match state {
+            GuessState::Correct => 'g',
+            GuessState::Misplace => 'y',
+            GuessState::Wrong => 'b',
+        })
+        .collect()
+}
+
+fn feedback_from_str(text: &str) -> Option<Match> {
+    let states = text
+        .chars()
+        .map(|c| match c {
+            'g' => Some(GuessState::Correct),
+            'y' => Some(GuessState::Misplace),
+            'b' => Some(GuessState::Wrong),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(Match { states })
+}
+
+fn hint_to_json(entry: &HintEntry) -> Value {
+    let mut value = json!({
+        "kind": entry.hint.kind().name(),
+        "cost": entry.cost,
+        "round": entry.round,
+        "at": entry.at,
+    });
+    match &entry.hint {
+        Hint::RevealLetter { position, letter } => {
+            value["position"] = json!(position);
+            value["letter"] = json!(letter.to_string());
+        }
+        Hint::RemainingCount(count) => value["count"] = json!(count),
+        Hint::BestGuess(word) => value["word"] = json!(word),
+    }
+    value
+}
+
+fn hint_from_json(object: &Map<String, Value>) -> Option<HintEntry> {
+    let hint = match HintKind::parse(get_str(object, "kind")?)? {
+        HintKind::RevealLetter => Hint::RevealLetter {
+            position: get_u64(object, "position")? as usize,
+            letter: get_str(object, "letter")?.chars().next()?,
+        },
+        HintKind::RemainingCount => Hint::RemainingCount(get_u64(object, "count")? as usize),
+        HintKind::BestGuess => Hint::BestGuess(get_str(object, "word")?.to_string()),
+    };
+    Some(HintEntry {
+        hint,
+        cost: get_u64(object, "cost")? as u32,
+        round: get_u64(object, "round").unwrap_or(0) as usize,
+        at: get_u64(object, "at").unwrap_or(0),
+    })
+}
+
+fn get_u64(object: &Map<String, Value>, key: &str) -> Option<u64> {
+    object.get(key).and_then(Value::as_u64)
+}
+
+fn get_str<'a>(object: &'a Map<String, Value>, key: &str) -> Option<&'a str> {
+    object.get(key).and_then(Value::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fibble_transcript() -> (Game, String) {
+        let mut game = test_game("cigar");
+        game.set_lies(1).unwrap();
+        test_play(&mut game, "rebut");
+        let json = game.transcript().to_json();
+        (game, json)
+    }
+
+    #[test]
+    fn lying_games_replay_the_same_feedback() {
+        let (game, json) = fibble_transcript();
+        let transcript = Transcript::from_json(&json).unwrap();
+        let mut replay = transcript.replay(&game).unwrap();
+        let (entry, replayed) = replay.step().unwrap();
+        assert_eq!(entry.feedback, replayed);
+    }
+
+    #[test]
+    fn more_lies_than_letters_are_malformed() {
+        let (_, json) = fibble_transcript();
+        let json = json.replace("\"lies\": 1", "\"lies\": 9");
+        assert!(matches!(
+            Transcript::from_json(&json),
+            Err(Error::MalformedData { .. })
+        ));
+    }
+
+    #[test]
+    fn lies_without_an_honest_tile_do_not_replay() {
+        let (game, json) = fibble_transcript();
+        let json = json.replace("\"lies\": 1", "\"lies\": 5");
+        let transcript = Transcript::from_json(&json).unwrap();
+        assert!(matches!(
+            transcript.replay(&game),
+            Err(Error::InvalidLies(_))
+        ));
+    }
+}
@@ -0,0 +1,247 @@
+use super::alphabet::Alphabet;
+use super::error::{Error, Result};
+use super::game::{MAX_WORD_LENGTH, MIN_WORD_LENGTH};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::Path;
+
+const EMBEDDED_ANSWERS: &str = include_str!("../data/answer");
+const EMBEDDED_CANDIDATES: &str = include_str!("../data/candidate");
+
+/// A validated, duplicate-free list of words sharing one length and one
+/// alphabet. Each word is kept both as text and as symbol indices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordList {
+    words: Vec<String>,
+    encoded: Vec<Vec<u8>>,
+    word_length: usize,
+    alphabet: Alphabet,
+}
+
+impl WordList {
+    /// Build an English list from words. See [`WordList::from_words_in`].
+    pub fn from_words<I, S>(words: I, word_length: usize) -> Result<WordList>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self::from_words_in(Alphabet::english(), words, word_length)
+    }
+    /// Build a list from words, lowercasing them, checking length and
+    /// alphabet, and dropping duplicates while keeping the first occurrence.
+    /// Word lengths outside `MIN_WORD_LENGTH..=MAX_WORD_LENGTH` are refused.
+    pub fn from_words_in<I, S>(alphabet: Alphabet, words: I, word_length: usize) -> Result<WordList>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        if !(MIN_WORD_LENGTH..=MAX_WORD_LENGTH).contains(&word_length) {
+            return Err(Error::UnsupportedWordLength(word_length));
+        }
+        let mut seen = HashSet::new();
+        let mut list = Vec::new();
+        let mut encoded = Vec::new();
+        for word in words {
+            let word: String = word
+                .as_ref()
+                .trim()
+                .chars()
+                .map(|c| alphabet.normalize_char(c).unwrap_or(c))
+                .collect();
+            alphabet.check_word(&word, word_length)?;
+            if seen.insert(word.clone()) {
+                encoded.push(
+                    alphabet
+                        .encode(&word)
+                        .expect("word checked against alphabet"),
+                );
+                list.push(word);
+            }
+        }
+        if list.is_empty() {
+            return Err(Error::EmptyWordList { path: None });
+        }
+        Ok(WordList {
+            words: list,
+            encoded,
+            word_length,
+            alphabet,
+        })
+    }
+    /// Parse either JSON or newline-separated text.
+    ///
+    /// JSON is an array of strings, or an object holding the `words` array
+    /// and the `alphabet` they are spelled in. Text lists skip blank lines and
+    /// lines starting with `#`, except for an `# alphabet: <name>` line
+    /// naming the alphabet, see [`Alphabet::by_name`]. Lists without an alphabet are English.
+    pub fn parse(text: &str, word_length: usize) -> Result<WordList> {
+        Self::parse_from(text, word_length, None)
+    }
+    /// Load a list from a file in any format accepted by [`WordList::parse`].
+    pub fn load<P: AsRef<Path>>(path: P, word_length: usize) -> Result<WordList> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|source| Error::MissingDataFile {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse_from(&text, word_length, Some(path))
+    }
+    /// The five-letter answer list compiled into the binary.
+    pub fn embedded_answers() -> WordList {
+        Self::parse(EMBEDDED_ANSWERS, 5).expect("embedded answer list is valid")
+    }
+    /// The five-letter allowed-guess list compiled into the binary.
+    pub fn embedded_candidates() -> WordList {
+        Self::parse(EMBEDDED_CANDIDATES, 5).expect("embedded candidate list is valid")
+    }
+    fn parse_from(text: &str, word_length: usize, path: Option<&Path>) -> Result<WordList> {
+        let with_path = |result: Result<WordList>| {
+            result.map_err(|err| match err {
+                Error::EmptyWordList { path: None } => Error::EmptyWordList {
+                    path: path.map(Path::to_path_buf),
+                },
+                err => err,
+            })
+        };
+        let malformed_json = |source| Error::MalformedJson {
+            path: path.map(Path::to_path_buf),
+            source,
+        };
+        let bad_alphabet = |err: Error| Error::MalformedData {
+            path: path.map(Path::to_path_buf),
+            reason: err.to_string(),
+        };
+        let trimmed = text.trim_start();
+        if trimmed.starts_with('[') {
+            let words: Vec<String> = serde_json::from_str(text).map_err(malformed_json)?;
+            with_path(Self::from_words(words, word_length))
+        } else if trimmed.starts_with('{') {
+            let mut value: Value = serde_json::from_str(text).map_err(malformed_json)?;
+            let alphabet = match value["alphabet"].as_str() {
+                Some(name) => Alphabet::by_name(name).map_err(bad_alphabet)?,
+                None => Alphabet::english(),
+            };
+            let words: Vec<String> =
+                serde_json::from_value(value["words"].take()).map_err(malformed_json)?;
+            with_path(Self::from_words_in(alphabet, words, word_length))
+        } else {
+            let mut alphabet = Alphabet::english();
+            for line in text.lines().map(str::trim) {
+                if let Some(name) = line
+                    .strip_prefix('#')
+                    .and_then(|rest| rest.trim().strip_prefix("alphabet:"))
+                {
+                    alphabet = Alphabet::by_name(name).map_err(bad_alphabet)?;
+                }
+            }
+            let words = text
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'));
+            with_path(Self::from_words_in(alphabet, words, word_length))
+        }
+    }
+    /// This list followed by the words of `other` it does not already hold.
+    pub fn union(&self, other: &WordList) -> Result<WordList> {
+        if self.word_length != other.word_length {
+            return Err(Error::LengthMismatch {
+                expected: self.word_length,
+                found: other.word_length,
+            });
+        }
+        if self.alphabet != other.alphabet {
+            return Err(Error::AlphabetMismatch {
+                expected: self.alphabet.name().to_string(),
+                found: other.alphabet.name().to_string(),
+            });
+        }
+        Self::from_words_in(
+            self.alphabet.clone(),
+            self.words.iter().chain(other.words.iter()),
+            self.word_length,
+        )
+    }
+    /// FNV-1a hash of the words and their order, identifying this exact list.
+    pub fn checksum(&self) -> u64 {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for word in self.words.iter() {
+            for byte in word.bytes().chain([b'\n']) {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        }
+        hash
+    }
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+    /// Words as symbol indices into [`WordList::alphabet`], in list order.
+    pub fn encoded(&self) -> &[Vec<u8>] {
+        &self.encoded
+    }
+    pub fn word_length(&self) -> usize {
+        self.word_length
+    }
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+    pub fn get(&self, index: usize) -> Option<&String> {
+        self.words.get(index)
+    }
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.iter().any(|w| w == word)
+    }
+    /// Index of `word` in the list.
+    pub fn position(&self, word: &str) -> Option<usize> {
+        self.words.iter().position(|w| w == word)
+    }
+    pub fn iter(&self) -> std::slice::Iter<'_, String> {
+        self.words.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unsupported_lengths_are_refused() {
+        let long = WordList::from_words(["abcdefghijkl", "abcdefghijkm"], 12);
+        assert!(matches!(long, Err(Error::UnsupportedWordLength(12))));
+        let short = WordList::from_words(["abc"], 3);
+        assert!(matches!(short, Err(Error::UnsupportedWordLength(3))));
+    }
+
+    #[test]
+    fn duplicates_are_dropped_and_case_folded() {
+        let list = WordList::from_words(["Cigar", "rebut", "cigar"], 5).unwrap();
+        assert_eq!(list.words(), ["cigar", "rebut"]);
+        assert_eq!(list.position("rebut"), Some(1));
+    }
+
+    #[test]
+    fn headers_name_the_alphabet() {
+        let text = "# alphabet: symbols:abcdefghijklmnñopqrstuvwxyz\nniño\nnino";
+        let list = WordList::parse(text, 4).unwrap();
+        assert_eq!(list.alphabet().name(), "custom");
+        assert_eq!(list.words(), ["niño", "nino"]);
+
+        let json = r#"{"alphabet": "abc", "words": ["cab"]}"#;
+        assert!(matches!(
+            WordList::parse(json, 4),
+            Err(Error::MalformedData { .. })
+        ));
+    }
+
+    #[test]
+    fn words_of_the_wrong_length_are_refused() {
+        let list = WordList::from_words(["cigar", "rebuts"], 5);
+        assert!(matches!(list, Err(Error::WrongLength { .. })));
+    }
+}